@@ -0,0 +1,39 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Schedulability analysis of a scheduler configuration on the host.
+//!
+//! Copy the `define_sched!` of your application with the `wcet` figures into this file
+//! and run it with `cargo run --example rta`.
+//! The exit code is non-zero, if a task can miss its deadline.
+
+use std::process::ExitCode;
+
+timeslice::define_sched! {
+    name: sched_main,
+    num_objs: 1,
+    tasks: {
+        { name: task_10ms,  period: 10 ms,  cpu: 0, prio: 9, stack: 16 kiB, wcet: 2 ms },
+        { name: task_50ms,  period: 50 ms,  cpu: 0, prio: 8, stack: 3 kiB,  wcet: 12 ms },
+        { name: task_100ms, period: 100 ms, cpu: 1, prio: 7, stack: 16 kiB, wcet: 40 ms },
+    },
+}
+
+fn main() -> ExitCode {
+    let analysis = sched_main::analysis(None);
+    print!("{analysis}");
+    if analysis.is_schedulable() {
+        ExitCode::SUCCESS
+    } else {
+        println!("The configuration is NOT schedulable.");
+        ExitCode::FAILURE
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
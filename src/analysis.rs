@@ -0,0 +1,241 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! # Schedulability analysis
+//!
+//! Offline utilization-bound and response-time analysis of the tasks of a scheduler.
+//!
+//! The generated `analysis()` function of a scheduler runs the analysis
+//! with the `wcet` figures declared in `define_sched!`.
+//! For tasks without declared `wcet` the maximum measured runtime
+//! from an optional [crate::stats::RtStats] snapshot is used.
+//!
+//! The analysis assumes preemptive fixed priority scheduling on each CPU core,
+//! deadlines equal to the periods and that all tasks can be released at the same time.
+//! The phase offsets are ignored, which makes the analysis pessimistic.
+//! Tasks with the same priority on the same core are accounted as
+//! interference for each other.
+//!
+//! Example:
+//!
+//! ```
+//! use timeslice::analysis::{TaskParams, Verdict, analyze};
+//! use std::time::Duration;
+//!
+//! let ms = Duration::from_millis;
+//! let analysis = analyze(&[
+//!     TaskParams { name: "task_10ms", core: 0, prio: 9, period: ms(10), wcet: Some(ms(3)) },
+//!     TaskParams { name: "task_50ms", core: 0, prio: 8, period: ms(50), wcet: Some(ms(20)) },
+//! ]);
+//! assert_eq!(analysis.tasks[1].response, Some(ms(29)));
+//! assert_eq!(analysis.tasks[1].verdict, Verdict::Meets);
+//! assert!(analysis.is_schedulable());
+//! ```
+
+use crate::stats::periodfmt;
+use core::{fmt, time::Duration};
+
+/// Parameters of one task.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskParams {
+    /// The task name.
+    pub name: &'static str,
+    /// The CPU core the task runs on.
+    pub core: usize,
+    /// The task priority. A higher number is a higher priority.
+    pub prio: u8,
+    /// The task period. This is also the deadline.
+    pub period: Duration,
+    /// The worst case execution time of one activation, if known.
+    pub wcet: Option<Duration>,
+}
+
+/// Result of the analysis of one task.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// The worst case response time is not longer than the period.
+    Meets,
+    /// The worst case response time is longer than the period.
+    Misses,
+    /// The WCET of this task or of an interfering task is unknown.
+    Unknown,
+}
+
+/// Analysis of one task.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskAnalysis {
+    /// The task parameters.
+    pub params: TaskParams,
+    /// The worst case response time.
+    ///
+    /// This is `None`, if the task misses its deadline or if the verdict is unknown.
+    pub response: Option<Duration>,
+    /// The analysis result.
+    pub verdict: Verdict,
+}
+
+/// Utilization of one CPU core.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoreAnalysis {
+    /// The CPU core number.
+    pub core: usize,
+    /// The sum of WCET / period of all tasks on this core.
+    ///
+    /// This is `None`, if the WCET of any task on this core is unknown.
+    pub utilization: Option<f64>,
+    /// The rate monotonic utilization bound `n * (2^(1/n) - 1)` for the `n` tasks on this core.
+    ///
+    /// If the utilization does not exceed the bound and the priorities are rate monotonic,
+    /// then all tasks on this core are schedulable.
+    pub bound: f64,
+}
+
+/// Schedulability analysis of all tasks.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Analysis {
+    /// The utilization of all CPU cores that run tasks.
+    pub cores: Vec<CoreAnalysis>,
+    /// The analysis of all tasks, in the order of the input.
+    pub tasks: Vec<TaskAnalysis>,
+}
+
+impl Analysis {
+    /// All tasks meet their deadlines.
+    pub fn is_schedulable(&self) -> bool {
+        self.tasks.iter().all(|t| t.verdict == Verdict::Meets)
+    }
+}
+
+/// Calculate the worst case response time of `task` with the interference of `hp`.
+///
+/// Returns `None`, if the response time exceeds the deadline.
+fn response_time(task: &TaskParams, wcet_us: u64, hp: &[(u64, u64)]) -> Option<u64> {
+    let deadline_us = task.period.as_micros() as u64;
+    let mut response_us = wcet_us + hp.iter().map(|(_, c)| c).sum::<u64>();
+    loop {
+        if response_us > deadline_us {
+            return None;
+        }
+        let next_us = wcet_us
+            + hp.iter()
+                .map(|(t, c)| response_us.div_ceil(*t) * c)
+                .sum::<u64>();
+        if next_us == response_us {
+            return Some(response_us);
+        }
+        response_us = next_us;
+    }
+}
+
+/// Run the utilization-bound and response-time analysis.
+pub fn analyze(tasks: &[TaskParams]) -> Analysis {
+    let mut cores: Vec<usize> = tasks.iter().map(|t| t.core).collect();
+    cores.sort_unstable();
+    cores.dedup();
+
+    let cores = cores
+        .into_iter()
+        .map(|core| {
+            let on_core: Vec<&TaskParams> = tasks.iter().filter(|t| t.core == core).collect();
+            let n = on_core.len() as f64;
+            let utilization = on_core
+                .iter()
+                .map(|t| Some(t.wcet?.as_secs_f64() / t.period.as_secs_f64()))
+                .sum();
+            CoreAnalysis {
+                core,
+                utilization,
+                bound: n * (2.0_f64.powf(1.0 / n) - 1.0),
+            }
+        })
+        .collect();
+
+    let tasks = tasks
+        .iter()
+        .enumerate()
+        .map(|(i, task)| {
+            let hp: Option<Vec<(u64, u64)>> = tasks
+                .iter()
+                .enumerate()
+                .filter(|(j, t)| *j != i && t.core == task.core && t.prio >= task.prio)
+                .map(|(_, t)| {
+                    Some((
+                        (t.period.as_micros() as u64).max(1),
+                        t.wcet?.as_micros() as u64,
+                    ))
+                })
+                .collect();
+            let (response, verdict) = match (task.wcet, hp) {
+                (Some(wcet), Some(hp)) => match response_time(task, wcet.as_micros() as u64, &hp) {
+                    Some(response_us) => (Some(Duration::from_micros(response_us)), Verdict::Meets),
+                    None => (None, Verdict::Misses),
+                },
+                _ => (None, Verdict::Unknown),
+            };
+            TaskAnalysis {
+                params: task.clone(),
+                response,
+                verdict,
+            }
+        })
+        .collect();
+
+    Analysis { cores, tasks }
+}
+
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for core in &self.cores {
+            match core.utilization {
+                Some(utilization) => write!(
+                    f,
+                    "CPU {}: utilization {:.1} %",
+                    core.core,
+                    utilization * 100.0
+                )?,
+                None => write!(f, "CPU {}: utilization unknown", core.core)?,
+            }
+            writeln!(f, "; RM bound {:.1} %", core.bound * 100.0)?;
+        }
+        for task in &self.tasks {
+            let params = &task.params;
+            write!(
+                f,
+                "{}, {} @ CPU {}, prio {}: ",
+                params.name,
+                periodfmt(params.period),
+                params.core,
+                params.prio
+            )?;
+            match params.wcet {
+                Some(wcet) => write!(f, "WCET {}", periodfmt(wcet))?,
+                None => write!(f, "WCET unknown")?,
+            }
+            match task.verdict {
+                Verdict::Meets => {
+                    let response = task.response.unwrap_or_default();
+                    writeln!(f, "; response {}; OK", periodfmt(response))?;
+                }
+                Verdict::Misses => {
+                    writeln!(
+                        f,
+                        "; response > {}; DEADLINE MISS",
+                        periodfmt(params.period)
+                    )?;
+                }
+                Verdict::Unknown => {
+                    writeln!(f, "; response unknown")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,51 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+use core::time::Duration;
+
+/// Context of one task activation.
+///
+/// All timestamps are monotonic microseconds of the hal clock.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskCtx {
+    /// The activation counter. The first activation of a task is number 0.
+    pub activation: u64,
+    /// The nominal release timestamp of this activation.
+    pub release_us: u64,
+    /// The actual start timestamp of this activation.
+    pub start_us: u64,
+    /// The actual start timestamp of the previous activation.
+    /// This is `None` for the first activation.
+    pub prev_start_us: Option<u64>,
+    /// The task period.
+    pub period: Duration,
+    /// The number of releases that have been dropped since the last activation.
+    pub missed: u32,
+}
+
+impl TaskCtx {
+    /// Get the real elapsed time since the start of the previous activation.
+    ///
+    /// For the first activation this is the nominal task period.
+    pub fn dt(&self) -> Duration {
+        match self.prev_start_us {
+            Some(prev_start_us) => {
+                Duration::from_micros(self.start_us.saturating_sub(prev_start_us))
+            }
+            None => self.period,
+        }
+    }
+
+    /// Get the delay between the nominal release and the actual start of this activation.
+    pub fn latency(&self) -> Duration {
+        Duration::from_micros(self.start_us.saturating_sub(self.release_us))
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
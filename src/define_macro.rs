@@ -7,20 +7,122 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 //
 
+/// Check whether an optional macro parameter is present.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __timeslice_present {
+    () => {
+        false
+    };
+    ($($tokens:tt)+) => {
+        true
+    };
+}
+
+/// Get the thread name of a task from the optional `thread_name` task parameter.
+///
+/// The default name is `task@sched`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __timeslice_thread_name {
+    ($name:ident, $taskname:ident) => {
+        core::concat!(core::stringify!($taskname), "@", core::stringify!($name))
+    };
+    ($name:ident, $taskname:ident, $thread_name:literal) => {
+        $thread_name
+    };
+}
+
+/// Convert the `prio` task parameter.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __timeslice_prio {
+    (auto, $auto:expr) => {
+        $auto
+    };
+    ($prio:literal, $auto:expr) => {
+        $prio
+    };
+}
+
+/// Convert the optional `on_overrun` task parameter to an `OverrunPolicy`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __timeslice_overrun_policy {
+    () => {
+        $crate::trigger::OverrunPolicy::Queue(1)
+    };
+    (skip) => {
+        $crate::trigger::OverrunPolicy::Skip
+    };
+    (queue($max_pending:literal)) => {
+        $crate::trigger::OverrunPolicy::Queue($max_pending)
+    };
+    (callback) => {
+        $crate::trigger::OverrunPolicy::Callback
+    };
+}
+
+/// Convert the optional `on_panic` task parameter to a `PanicPolicy`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __timeslice_panic_policy {
+    () => {
+        $crate::fault::PanicPolicy::Continue
+    };
+    (continue) => {
+        $crate::fault::PanicPolicy::Continue
+    };
+    (disable) => {
+        $crate::fault::PanicPolicy::Disable
+    };
+    (fault) => {
+        $crate::fault::PanicPolicy::Fault
+    };
+}
+
+/// Convert an optional time with unit `us`, `ms` or `s` to microseconds.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __timeslice_us {
+    () => {
+        0
+    };
+    ($value:literal us) => {
+        $crate::period::to_us($value, 1)
+    };
+    ($value:literal ms) => {
+        $crate::period::to_us($value, 1_000)
+    };
+    ($value:literal s) => {
+        $crate::period::to_us($value, 1_000_000)
+    };
+}
+
 /// Define a scheduler
 #[macro_export]
 macro_rules! define_sched {
     (
         name: $name:ident,
         num_objs: $num_objs:literal,
+        $(auto_offsets: $auto_offsets:literal,)?
+        $(allow_prio_inversion: $allow_prio_inversion:literal,)?
+        $(group_threads: $group_threads:literal,)?
         tasks: {
             $(
                 {
                     name: $taskname:ident,
-                    period: $timebase:literal ms,
+                    period: $period:literal $period_unit:ident,
+                    $(offset: $offset:literal $offset_unit:ident,)?
                     cpu: $core:literal,
-                    prio: $prio:literal,
+                    prio: $prio:tt,
                     stack: $stack_kib:literal kiB
+                    $(, budget: $budget:literal $budget_unit:ident )?
+                    $(, wcet: $wcet:literal $wcet_unit:ident )?
+                    $(, thread_name: $thread_name:literal )?
+                    $(, on_overrun: $on_overrun:ident $(($overrun_arg:literal))? )?
+                    $(, on_panic: $on_panic:tt )?
+                    $(,)?
                 }
             ),* $(,)?
         } $(,)?
@@ -33,21 +135,313 @@ macro_rules! define_sched {
                             AtomicBool,
                             AtomicU32,
                             Ordering::{
+                                Acquire,
                                 Relaxed,
+                                Release,
                                 SeqCst,
                             },
                             fence,
                         },
                         Arc,
-                        Condvar,
                         Mutex,
                         LazyLock,
                     },
-                    thread,
                     time::Duration,
                     ffi::CStr,
                 };
-                use $crate::meas::RuntimeMeas;
+                use $crate::{
+                    fault::PanicPolicy,
+                    meas::RuntimeMeas,
+                    trigger::{OverrunPolicy, Signal, Trigger},
+                };
+
+                // Report a duplicate task name at the name of the duplicate task.
+                #[allow(dead_code)]
+                fn unique_task_names($( $taskname: (), )*) {}
+
+                $(
+                    /// The task period, in microseconds.
+                    const [<$taskname:upper _PERIOD_US>]: u64 = $crate::__timeslice_us!($period $period_unit);
+
+                    /// The task phase offset, in microseconds.
+                    const [<$taskname:upper _OFFSET_US>]: u64 = $crate::__timeslice_us!($($offset $offset_unit)?);
+
+                    /// The task phase offset has been specified and is fixed.
+                    const [<$taskname:upper _OFFSET_FIXED>]: bool = $crate::__timeslice_present!($($offset)?);
+
+                    /// The task priority.
+                    const [<$taskname:upper _PRIO>]: u8 = $crate::__timeslice_prio!(
+                        $prio,
+                        $crate::period::rm_prio(PERIODS_US, TASK_CORES, TaskId::$taskname as usize, 9)
+                    );
+
+                    /// The declared task worst case execution time, in microseconds.
+                    const [<$taskname:upper _WCET_US>]: Option<u64> =
+                        if $crate::__timeslice_present!($($wcet)?) {
+                            Some($crate::__timeslice_us!($($wcet $wcet_unit)?))
+                        } else {
+                            None
+                        };
+
+                    /// The task execution time budget, in microseconds. 0 means no budget.
+                    const [<$taskname:upper _BUDGET_US>]: u64 = $crate::__timeslice_us!($($budget $budget_unit)?);
+                )*
+
+                // Compile time validation of the scheduler configuration.
+                const _: () = assert!(($num_objs) > 0, "num_objs must not be zero");
+                $(
+                    const _: () = assert!(
+                        [<$taskname:upper _PERIOD_US>] > 0,
+                        core::concat!("Task `", core::stringify!($taskname), "`: period must not be zero")
+                    );
+                    const _: () = assert!(
+                        [<$taskname:upper _OFFSET_US>] < [<$taskname:upper _PERIOD_US>],
+                        core::concat!("Task `", core::stringify!($taskname), "`: offset must be shorter than the period")
+                    );
+                    const _: () = assert!(
+                        ($core) < $crate::hal::CORES,
+                        core::concat!("Task `", core::stringify!($taskname), "`: cpu does not exist on the selected hal")
+                    );
+                    const _: () = assert!(
+                        [<$taskname:upper _PRIO>] < 10,
+                        core::concat!("Task `", core::stringify!($taskname), "`: prio must be a number in the range 0..=9")
+                    );
+                    const _: () = assert!(
+                        $crate::names::ops_is_unique(TASK_NAMES, TaskId::$taskname as usize),
+                        core::concat!(
+                            "Task `", core::stringify!($taskname),
+                            "`: name clashes with an Ops method. ",
+                            "Task names must not be `sched_overrun`, `sched_obj_name` or another task name plus `_ctx`"
+                        )
+                    );
+                    const _: () = assert!(
+                        ($stack_kib) > 0,
+                        core::concat!("Task `", core::stringify!($taskname), "`: stack must not be zero")
+                    );
+                    const _: () = assert!(
+                        !$crate::__timeslice_present!($($budget)?)
+                            || ([<$taskname:upper _BUDGET_US>] > 0
+                                && [<$taskname:upper _BUDGET_US>] <= [<$taskname:upper _PERIOD_US>]),
+                        core::concat!("Task `", core::stringify!($taskname), "`: budget must be in the range 1 us..=period")
+                    );
+                )*
+
+                /// All task periods, in microseconds.
+                const PERIODS_US: &[u64] = &[$([<$taskname:upper _PERIOD_US>]),*];
+
+                /// The CPU cores of all tasks.
+                const TASK_CORES: &[usize] = &[$($core),*];
+
+                /// The names of all tasks.
+                const TASK_NAMES: &[&str] = &[$(core::stringify!($taskname)),*];
+
+                /// The priorities of all tasks.
+                const TASK_PRIOS: &[u8] = &[$([<$taskname:upper _PRIO>]),*];
+
+                /// Allow priorities that contradict the rate monotonic order.
+                const ALLOW_PRIO_INVERSION: bool =
+                    $crate::__timeslice_present!($($allow_prio_inversion)?) $(&& $allow_prio_inversion)?;
+
+                $(
+                    const _: () = assert!(
+                        ALLOW_PRIO_INVERSION
+                            || !$crate::period::rm_inverted(
+                                PERIODS_US, TASK_CORES, TASK_PRIOS, TaskId::$taskname as usize
+                            ),
+                        core::concat!(
+                            "Task `", core::stringify!($taskname),
+                            "`: prio is lower than the prio of a task with a longer period on the same cpu. ",
+                            "Use `prio: auto` or the scheduler option `allow_prio_inversion: true`"
+                        )
+                    );
+                )*
+
+                /// Run tasks with the same cpu and prio in one thread.
+                const GROUP_THREADS: bool =
+                    $crate::__timeslice_present!($($group_threads)?) $(&& $group_threads)?;
+
+                $(
+                    /// The index of the thread that runs the task.
+                    const [<$taskname:upper _THREAD>]: usize = $crate::group::thread(
+                        TASK_CORES, TASK_PRIOS, TaskId::$taskname as usize, GROUP_THREADS
+                    );
+
+                    /// The stack size of the thread that runs the task, in bytes.
+                    const [<$taskname:upper _THREAD_STACK>]: usize = $crate::group::stack_size(
+                        TASK_THREADS, TASK_STACKS, [<$taskname:upper _THREAD>]
+                    );
+                )*
+
+                /// The thread indices of all tasks.
+                const TASK_THREADS: &[usize] = &[$([<$taskname:upper _THREAD>]),*];
+
+                $(
+                    /// The name of the thread, if the task is the first task of its thread.
+                    const [<$taskname:upper _THREAD_NAME>]: [u8; $crate::group::NAME_BUF_LEN] =
+                        $crate::group::name($crate::__timeslice_thread_name!($name, $taskname $(, $thread_name)?));
+
+                    const _: () = assert!(
+                        !$crate::__timeslice_present!($($thread_name)?)
+                            || [<$taskname:upper _THREAD>] == TaskId::$taskname as usize,
+                        core::concat!(
+                            "Task `", core::stringify!($taskname),
+                            "`: thread_name is only allowed on the first task of a thread group"
+                        )
+                    );
+                    const _: () = assert!(
+                        [<$taskname:upper _THREAD_NAME>][0] != 0,
+                        core::concat!("Task `", core::stringify!($taskname), "`: thread_name must not be empty")
+                    );
+                    const _: () = assert!(
+                        [<$taskname:upper _THREAD>] != TaskId::$taskname as usize
+                            || $crate::group::name_is_unique(
+                                &THREAD_NAMES, TASK_THREADS, TaskId::$taskname as usize
+                            ),
+                        core::concat!("Task `", core::stringify!($taskname), "`: thread name is not unique")
+                    );
+                )*
+
+                /// The thread names, indexed by the thread index.
+                static THREAD_NAMES: [[u8; $crate::group::NAME_BUF_LEN]; TASK_COUNT] =
+                    [$([<$taskname:upper _THREAD_NAME>]),*];
+
+                /// The stack sizes of all tasks, in bytes.
+                const TASK_STACKS: &[usize] = &[$(($stack_kib) * 1024),*];
+
+                /// All task phase offsets, in microseconds.
+                const OFFSETS_US: &[u64] = &[$([<$taskname:upper _OFFSET_US>]),*];
+
+                /// The base tick period, in microseconds.
+                /// This is the greatest common divisor of all task periods and offsets.
+                const BASE_PERIOD_US: u64 = $crate::period::base_period(PERIODS_US, OFFSETS_US);
+
+                /// The hyperperiod, in microseconds.
+                /// This is the least common multiple of all task periods.
+                const HYPERPERIOD_US: u64 = $crate::period::hyperperiod(PERIODS_US);
+
+                /// Automatically assign the phase offsets of the tasks without fixed offset at init.
+                const AUTO_OFFSETS: bool = $crate::__timeslice_present!($($auto_offsets)?) $(&& $auto_offsets)?;
+
+                /// The number of base ticks per hyperperiod.
+                const HYPERPERIOD_TICKS: u32 = {
+                    let ticks = HYPERPERIOD_US / BASE_PERIOD_US;
+                    assert!(ticks <= u32::MAX as u64, "The hyperperiod has too many base ticks");
+                    ticks as u32
+                };
+
+                const _: () = assert!(
+                    !AUTO_OFFSETS || HYPERPERIOD_TICKS as u64 <= $crate::schedule::MAX_AUTO_OFFSETS_TICKS,
+                    "auto_offsets: The hyperperiod has more than 4096 base ticks. Use harmonic task periods or static offsets"
+                );
+
+                const _: () = assert!(
+                    BASE_PERIOD_US >= $crate::hal::MIN_PERIOD_US,
+                    "The base period (GCD of all task periods and offsets) is shorter than the selected hal can handle"
+                );
+
+                /// Time slice scheduler task identifier.
+                #[allow(non_camel_case_types)]
+                #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+                pub enum TaskId {
+                    $(
+                        $taskname,
+                    )*
+                }
+
+                /// The number of tasks.
+                const TASK_COUNT: usize = [$(TaskId::$taskname),*].len();
+
+                /// The declared configuration of all tasks, in the order of definition.
+                pub const TASKS: [$crate::TaskInfo; TASK_COUNT] = [
+                    $(
+                        $crate::TaskInfo {
+                            name: core::stringify!($taskname),
+                            period: Duration::from_micros([<$taskname:upper _PERIOD_US>]),
+                            offset: if [<$taskname:upper _OFFSET_FIXED>] {
+                                Some(Duration::from_micros([<$taskname:upper _OFFSET_US>]))
+                            } else {
+                                None
+                            },
+                            core: $core,
+                            prio: [<$taskname:upper _PRIO>],
+                            stack_size: ($stack_kib) * 1024,
+                            budget: match [<$taskname:upper _BUDGET_US>] {
+                                0 => None,
+                                budget => Some(Duration::from_micros(budget)),
+                            },
+                            wcet: match [<$taskname:upper _WCET_US>] {
+                                Some(wcet) => Some(Duration::from_micros(wcet)),
+                                None => None,
+                            },
+                        },
+                    )*
+                ];
+
+                /// Get the base tick period.
+                ///
+                /// This is the greatest common divisor of all task periods and offsets.
+                pub const fn base_period() -> Duration {
+                    Duration::from_micros(BASE_PERIOD_US)
+                }
+
+                /// Get the hyperperiod.
+                ///
+                /// This is the least common multiple of all task periods.
+                /// The schedule repeats after each hyperperiod.
+                pub const fn hyperperiod() -> Duration {
+                    Duration::from_micros(HYPERPERIOD_US)
+                }
+
+                impl TaskId {
+                    /// All tasks, in the order of definition.
+                    pub const ALL: [TaskId; TASK_COUNT] = [$(TaskId::$taskname),*];
+
+                    /// Get the index of the task.
+                    ///
+                    /// The tasks are numbered in the order of their definition, starting at 0.
+                    pub const fn index(self) -> usize {
+                        self as usize
+                    }
+
+                    /// Get the declared configuration of the task.
+                    pub const fn info(self) -> $crate::TaskInfo {
+                        TASKS[self.index()]
+                    }
+
+                    /// Get the name of the task.
+                    pub const fn name(self) -> &'static str {
+                        TASKS[self.index()].name
+                    }
+
+                    /// Get the period of the task.
+                    pub const fn period(self) -> Duration {
+                        TASKS[self.index()].period
+                    }
+
+                    /// Get the CPU core the task runs on.
+                    pub const fn core(self) -> usize {
+                        TASKS[self.index()].core
+                    }
+
+                    /// Get the priority of the task.
+                    pub const fn prio(self) -> u8 {
+                        TASKS[self.index()].prio
+                    }
+
+                    /// Get the declared stack size of the task, in bytes.
+                    pub const fn stack_size(self) -> usize {
+                        TASKS[self.index()].stack_size
+                    }
+
+                    /// Get the name of the thread that runs the task.
+                    ///
+                    /// This is the `thread_name` of the task or the task name,
+                    /// truncated to the thread name length limit of the hal.
+                    /// Grouped tasks share the thread name of the first task of the group.
+                    pub fn thread_name(self) -> &'static str {
+                        $crate::group::name_str(&THREAD_NAMES[TASK_THREADS[self.index()]])
+                    }
+                }
 
                 /// Time slice scheduler tasks.
                 pub trait Ops {
@@ -56,22 +450,73 @@ macro_rules! define_sched {
                         fn $taskname(&self) {
                         }
                     )*
+
+                    $(
+                        /// Run the user code for this time base with the context of the activation.
+                        ///
+                        /// Implement this instead of the method without context,
+                        /// if the context is needed.
+                        fn [<$taskname _ctx>](&self, ctx: &$crate::TaskCtx) {
+                            let _ = ctx;
+                            self.$taskname();
+                        }
+                    )*
+
+                    /// Called before the next activation of a task with `on_overrun: callback`,
+                    /// if releases of the task have been dropped due to overruns.
+                    ///
+                    /// `missed` is the number of dropped releases.
+                    fn sched_overrun(&self, task: TaskId, missed: u32) {
+                        let _ = (task, missed);
+                    }
+
+                    /// Get the name of this object for the per-object runtime statistics.
+                    ///
+                    /// If this returns `None`, then the object is identified by its index.
+                    fn sched_obj_name(&self) -> Option<&'static str> {
+                        None
+                    }
                 }
 
                 /// Time slice scheduler handler trait object.
                 pub type OpsObject = Arc<dyn Ops + Send + Sync + 'static>;
 
+                /// Fault hook, that is called if a task handler panics.
+                ///
+                /// The arguments are the task, the index of the panicking object
+                /// and the panic payload.
+                pub type FaultHook = fn(TaskId, usize, &(dyn core::any::Any + Send));
+
+                /// The installed fault hook.
+                #[doc(hidden)]
+                static FAULT_HOOK: Mutex<Option<FaultHook>> = Mutex::new(None);
+
+                /// Budget hook, that is called if a task activation exceeds its execution time budget.
+                ///
+                /// The arguments are the task, the measured execution time and the budget.
+                pub type BudgetHook = fn(TaskId, Duration, Duration);
+
+                /// The installed budget hook.
+                #[doc(hidden)]
+                static BUDGET_HOOK: Mutex<Option<BudgetHook>> = Mutex::new(None);
+
                 /// Time slice scheduler.
                 #[doc(hidden)]
                 pub struct TimeSliceSched {
                     initialized: AtomicBool,
-                    baseperiod: AtomicU32,
                     count: AtomicU32,
-                    count_mod: AtomicU32,
+                    paused: AtomicBool,
+                    offsets_changed: AtomicBool,
+                    signals: [Signal<TASK_COUNT>; TASK_COUNT],
+                    stack_probes: [$crate::hal::StackProbe; TASK_COUNT],
                     $(
-                        [<trigflag_ $taskname>]: Arc<(Mutex<bool>, Condvar)>,
+                        [<trigger_ $taskname>]: Trigger,
+                        [<enabled_ $taskname>]: AtomicBool,
+                        [<offset_ticks_ $taskname>]: AtomicU32,
+                        [<next_offset_ticks_ $taskname>]: AtomicU32,
+                        [<budget_overruns_ $taskname>]: AtomicU32,
                     )*
-                    rt: RuntimeMeas,
+                    rt: RuntimeMeas<TASK_COUNT, $num_objs>,
                 }
 
                 /// Time slice scheduler instance.
@@ -79,38 +524,333 @@ macro_rules! define_sched {
                 static TIMESLICESCHED: LazyLock<TimeSliceSched> = LazyLock::new(|| {
                     TimeSliceSched {
                         initialized: AtomicBool::new(false),
-                        baseperiod: AtomicU32::new(0),
                         count: AtomicU32::new(0),
-                        count_mod: AtomicU32::new(0),
+                        paused: AtomicBool::new(false),
+                        offsets_changed: AtomicBool::new(false),
+                        signals: [const { Signal::new() }; TASK_COUNT],
+                        stack_probes: [const { $crate::hal::StackProbe::new() }; TASK_COUNT],
                         $(
-                            [<trigflag_ $taskname>]: Arc::new((Mutex::new(false),
-                                                               Condvar::new())),
+                            [<trigger_ $taskname>]: Trigger::new([<$taskname:upper _PERIOD_US>]),
+                            [<enabled_ $taskname>]: AtomicBool::new(true),
+                            [<offset_ticks_ $taskname>]: AtomicU32::new(0),
+                            [<next_offset_ticks_ $taskname>]: AtomicU32::new(0),
+                            [<budget_overruns_ $taskname>]: AtomicU32::new(0),
                         )*
                         rt: RuntimeMeas::new(),
                     }
                 });
 
+                /// Cleans up a task thread when the thread exits.
+                #[doc(hidden)]
+                struct TaskThreadGuard(usize);
+
+                impl Drop for TaskThreadGuard {
+                    fn drop(&mut self) {
+                        let thread = self.0;
+                        TIMESLICESCHED.stack_probes[thread].reset();
+                        // Drop the pending activations of the tasks of this thread.
+                        // This runs on the task thread, so that the hal accounts the
+                        // completions in the same context as the releases.
+                        $(
+                            if [<$taskname:upper _THREAD>] == thread {
+                                TIMESLICESCHED.[<trigger_ $taskname>].drop_pending();
+                            }
+                        )*
+                    }
+                }
+
+                /// State of the tasks of one task thread.
+                #[doc(hidden)]
+                struct TaskThreadState {
+                    /// The objects that are enabled for each task.
+                    obj_enabled: [[bool; $num_objs]; TASK_COUNT],
+                    /// The start timestamp of the previous activation of each task.
+                    prev_start_us: [Option<u64>; TASK_COUNT],
+                }
+
+                impl TaskThreadState {
+                    fn new() -> Self {
+                        Self {
+                            obj_enabled: [[true; $num_objs]; TASK_COUNT],
+                            prev_start_us: [None; TASK_COUNT],
+                        }
+                    }
+                }
+
+                /// Operating system resources of the running scheduler.
+                #[doc(hidden)]
+                struct TimeSliceSchedOs {
+                    timer: $crate::hal::Timer<'static>,
+                    threads: Vec<$crate::hal::TaskHandle>,
+                    obj_names: [Option<&'static str>; $num_objs],
+                }
+
                 /// Time slice scheduler instance.
                 #[doc(hidden)]
-                static TIMESLICESCHED_OS: LazyLock<Mutex<Option<$crate::hal::Timer<'static>>>>
+                static TIMESLICESCHED_OS: LazyLock<Mutex<Option<TimeSliceSchedOs>>>
                     = LazyLock::new(|| Mutex::new(None));
 
                 /// Time slice scheduler initialization.
+                ///
+                /// The scheduler can be initialized again after `shutdown()`.
+                /// Each initialization starts with all tasks enabled and the scheduler not paused.
                 #[inline]
                 pub fn init(objs: [OpsObject; $num_objs]) {
                     TimeSliceSched::init(objs);
                 }
 
-                /// Print the task and CPU runtime load.
+                /// Shut down the time slice scheduler.
+                ///
+                /// This stops the base timer, lets the running tasks complete
+                /// their current activation and then joins all task threads.
+                /// Pending activations are dropped.
+                /// The registered objects are released.
+                ///
+                /// This must not be called from a task.
+                #[inline]
+                pub fn shutdown() {
+                    TimeSliceSched::shutdown();
+                }
+
+                /// Get a snapshot of the task and CPU runtime statistics.
+                ///
+                /// If `reset` is true, then a new measurement window is started.
+                pub fn rt_stats(reset: bool) -> $crate::stats::RtStats {
+                    let (window, cpus) = TIMESLICESCHED.rt.stats_cpus(reset);
+                    // Keep the lock, so that the task threads keep running
+                    // while their stack usage is measured.
+                    let os = TIMESLICESCHED_OS.lock().unwrap();
+                    let obj_names = os.as_ref().map(|os| os.obj_names).unwrap_or([None; $num_objs]);
+                    let tasks = vec![
+                        $(
+                            {
+                                let meas = TIMESLICESCHED.rt.stats_task(
+                                    TaskId::$taskname.index(),
+                                    reset
+                                );
+                                $crate::stats::RtTaskStats {
+                                    name: core::stringify!($taskname),
+                                    period: Duration::from_micros([<$taskname:upper _PERIOD_US>]),
+                                    core: $core,
+                                    count: meas.count,
+                                    runtime: meas.runtime,
+                                    jitter: meas.jitter,
+                                    response: meas.response,
+                                    percentiles: meas.percentiles,
+                                    stack_size: $crate::hal::thread_stack_size([<$taskname:upper _THREAD_STACK>]),
+                                    budget: match [<$taskname:upper _BUDGET_US>] {
+                                        0 => None,
+                                        budget => Some(Duration::from_micros(budget)),
+                                    },
+                                    budget_overruns: TIMESLICESCHED.[<budget_overruns_ $taskname>].load(Relaxed),
+                                    stack_used: if os.is_some() {
+                                        TIMESLICESCHED.stack_probes[[<$taskname:upper _THREAD>]].used()
+                                    } else {
+                                        None
+                                    },
+                                    objs: meas.objs.into_iter().enumerate().map(|(index, obj)| {
+                                        $crate::stats::RtObjStats {
+                                            index,
+                                            name: obj_names[index],
+                                            count: obj.count,
+                                            runtime: obj.runtime,
+                                        }
+                                    }).collect(),
+                                    overruns: TIMESLICESCHED.[<trigger_ $taskname>].overruns(),
+                                }
+                            },
+                        )*
+                    ];
+                    $crate::stats::RtStats {
+                        window,
+                        cpus,
+                        tasks,
+                    }
+                }
+
+                /// Print the task and CPU runtime load
+                /// and start a new measurement window.
                 pub fn rt_print() {
                     if TIMESLICESCHED.rt.is_enabled() {
-                        TIMESLICESCHED.rt.print_cpus();
+                        use std::io::Write as _;
+                        let stats = rt_stats(true);
+                        let _ = write!(std::io::stdout().lock(), "{stats}");
+                    }
+                }
+
+                /// Install a hook that is called, if a task handler panics.
+                ///
+                /// The hook is called for every caught panic,
+                /// before the `on_panic` policy of the task is applied.
+                /// A panic in the hook itself is caught and ignored.
+                pub fn set_fault_hook(hook: Option<FaultHook>) {
+                    *FAULT_HOOK.lock().unwrap() = hook;
+                }
+
+                /// Install or remove the budget hook.
+                ///
+                /// The hook is called from the task thread after every activation
+                /// that exceeded the `budget` of the task.
+                /// A panic in the hook is caught and ignored.
+                pub fn set_budget_hook(hook: Option<BudgetHook>) {
+                    *BUDGET_HOOK.lock().unwrap() = hook;
+                }
+
+                /// Get the number of activations of a task that exceeded the execution time budget
+                /// since the scheduler has been initialized.
+                pub fn budget_overrun_count(task: TaskId) -> u32 {
+                    match task {
                         $(
-                            TIMESLICESCHED.rt.print_task(
-                                core::stringify!($taskname),
-                                $timebase,
-                                $core
-                            );
+                            TaskId::$taskname => TIMESLICESCHED.[<budget_overruns_ $taskname>].load(Relaxed),
+                        )*
+                    }
+                }
+
+                /// Enable or disable a task.
+                ///
+                /// A disabled task is not released anymore.
+                /// An already running activation is completed.
+                pub fn set_task_enabled(task: TaskId, enabled: bool) {
+                    match task {
+                        $(
+                            TaskId::$taskname => TIMESLICESCHED.[<enabled_ $taskname>].store(enabled, Relaxed),
+                        )*
+                    }
+                }
+
+                /// Check whether a task is enabled.
+                pub fn is_task_enabled(task: TaskId) -> bool {
+                    match task {
+                        $(
+                            TaskId::$taskname => TIMESLICESCHED.[<enabled_ $taskname>].load(Relaxed),
+                        )*
+                    }
+                }
+
+                /// Pause the scheduler.
+                ///
+                /// No task is released while the scheduler is paused.
+                /// Already running activations are completed.
+                /// The base timer keeps running, so that the task phases are kept.
+                pub fn pause() {
+                    TIMESLICESCHED.paused.store(true, Relaxed);
+                }
+
+                /// Resume the paused scheduler.
+                pub fn resume() {
+                    TIMESLICESCHED.paused.store(false, Relaxed);
+                }
+
+                /// Check whether the scheduler is paused.
+                pub fn is_paused() -> bool {
+                    TIMESLICESCHED.paused.load(Relaxed)
+                }
+
+                /// Get the active schedule table.
+                pub fn schedule_table() -> $crate::schedule::ScheduleTable {
+                    $crate::schedule::ScheduleTable {
+                        base_period: Duration::from_micros(BASE_PERIOD_US),
+                        hyperperiod_ticks: HYPERPERIOD_TICKS as u64,
+                        tasks: vec![
+                            $(
+                                $crate::schedule::ScheduleEntry::new(
+                                    core::stringify!($taskname),
+                                    $core,
+                                    [<$taskname:upper _PERIOD_US>] / BASE_PERIOD_US,
+                                    TIMESLICESCHED.[<next_offset_ticks_ $taskname>].load(Relaxed) as u64,
+                                    [<$taskname:upper _OFFSET_FIXED>],
+                                    1,
+                                ),
+                            )*
+                        ],
+                    }
+                }
+
+                /// Get a schedule table with automatically assigned phase offsets
+                /// for all tasks without fixed offset.
+                ///
+                /// The offsets are assigned so that the peak number of releases
+                /// per base tick is minimized on each CPU core.
+                /// If runtime statistics are given, then the peak load per base tick is minimized,
+                /// with the load being the measured maximum task runtime.
+                ///
+                /// Panics, if the hyperperiod has more than `schedule::MAX_AUTO_OFFSETS_TICKS` base ticks.
+                pub fn auto_schedule_table(
+                    stats: Option<&$crate::stats::RtStats>
+                ) -> $crate::schedule::ScheduleTable {
+                    let mut table = schedule_table();
+                    for entry in &mut table.tasks {
+                        if !entry.fixed {
+                            entry.offset_ticks = 0;
+                        }
+                        let runtime = stats
+                            .and_then(|stats| stats.tasks.iter().find(|t| t.name == entry.name))
+                            .and_then(|task| task.runtime);
+                        if let Some(runtime) = runtime {
+                            entry.weight = (runtime.max.as_micros() as u64).max(1);
+                        }
+                    }
+                    table.assign_offsets();
+                    table
+                }
+
+                /// Apply the phase offsets of a schedule table.
+                ///
+                /// The table must have been created by `schedule_table()`
+                /// or `auto_schedule_table()` of this scheduler.
+                ///
+                /// The new offsets take effect for all tasks at once at the start of the next hyperperiod.
+                /// At this transition the interval between two releases of a task
+                /// with a changed offset is shortened or lengthened once.
+                pub fn apply_schedule_table(table: &$crate::schedule::ScheduleTable) {
+                    let mut entries = table.tasks.iter();
+                    $(
+                        let entry = entries.next().expect("Schedule table does not match the scheduler");
+                        assert!(
+                            entry.name == core::stringify!($taskname)
+                                && entry.offset_ticks < [<$taskname:upper _PERIOD_US>] / BASE_PERIOD_US,
+                            "Schedule table does not match the scheduler"
+                        );
+                        TIMESLICESCHED.[<next_offset_ticks_ $taskname>].store(entry.offset_ticks as u32, Relaxed);
+                    )*
+                    TIMESLICESCHED.offsets_changed.store(true, Release);
+                }
+
+                /// Run the schedulability analysis of the scheduler configuration.
+                ///
+                /// The declared `wcet` of the tasks is used.
+                /// For tasks without declared `wcet` the maximum measured runtime
+                /// from the optional statistics snapshot is used.
+                pub fn analysis(
+                    stats: Option<&$crate::stats::RtStats>
+                ) -> $crate::analysis::Analysis {
+                    let measured = |name: &str| {
+                        stats
+                            .and_then(|stats| stats.tasks.iter().find(|t| t.name == name))
+                            .and_then(|task| task.runtime)
+                            .map(|runtime| runtime.max)
+                    };
+                    $crate::analysis::analyze(&[
+                        $(
+                            $crate::analysis::TaskParams {
+                                name: core::stringify!($taskname),
+                                core: $core,
+                                prio: [<$taskname:upper _PRIO>],
+                                period: Duration::from_micros([<$taskname:upper _PERIOD_US>]),
+                                wcet: [<$taskname:upper _WCET_US>]
+                                    .map(Duration::from_micros)
+                                    .or_else(|| measured(core::stringify!($taskname))),
+                            },
+                        )*
+                    ])
+                }
+
+                /// Get the number of releases of a task that happened
+                /// while its previous activation was not completed, yet.
+                pub fn overrun_count(task: TaskId) -> u32 {
+                    match task {
+                        $(
+                            TaskId::$taskname => TIMESLICESCHED.[<trigger_ $taskname>].overruns(),
                         )*
                     }
                 }
@@ -125,90 +865,310 @@ macro_rules! define_sched {
                     TIMESLICESCHED.rt.enable(enable);
                 }
 
+                /// Check whether the per-object runtime measurement is enabled.
+                #[inline]
+                pub fn rt_objs_is_enabled() -> bool {
+                    TIMESLICESCHED.rt.objs_is_enabled()
+                }
+
+                /// Enable or disable the per-object runtime measurement.
+                ///
+                /// This measures the runtime of each object's task method separately.
+                /// It only takes effect while the runtime measurement is enabled with `rt_enable()`.
+                #[inline]
+                pub fn rt_enable_objs(enable: bool) {
+                    TIMESLICESCHED.rt.enable_objs(enable);
+                }
+
                 impl TimeSliceSched {
-                    /// Initialize the time slice scheduler, once.
+                    /// Initialize the time slice scheduler.
                     fn init(objs: [OpsObject; $num_objs]) {
-                        assert!(!TIMESLICESCHED.initialized.swap(true, Relaxed));
+                        assert!(
+                            !TIMESLICESCHED.initialized.swap(true, Relaxed),
+                            core::concat!("The scheduler `", core::stringify!($name), "` is already initialized")
+                        );
+                        let obj_names = core::array::from_fn(|i| objs[i].sched_obj_name());
                         let objs = Arc::new(objs);
+                        let mut threads = Vec::new();
 
-                        // Calculate base period and counter modulo.
-                        let mut min_timebase = u32::MAX;
-                        let mut max_timebase = u32::MIN;
-                        $(
-                            min_timebase = min_timebase.min($timebase);
-                            max_timebase = max_timebase.max($timebase);
-                        )*
-                        let baseperiod = min_timebase;
-                        let count_mod = max_timebase / baseperiod;
+                        for signal in &TIMESLICESCHED.signals {
+                            signal.reset();
+                        }
+                        for probe in &TIMESLICESCHED.stack_probes {
+                            probe.reset();
+                        }
+                        TIMESLICESCHED.paused.store(false, Relaxed);
                         $(
-                            assert!(($timebase) % baseperiod == 0, "All task periods must be multiples of the smallest task period");
+                            TIMESLICESCHED.[<enabled_ $taskname>].store(true, Relaxed);
+                            TIMESLICESCHED.[<trigger_ $taskname>].reset();
+                            TIMESLICESCHED.[<budget_overruns_ $taskname>].store(0, Relaxed);
                         )*
 
                         // Spawn all handler threads.
                         $(
-                            let thread_trigflag = Arc::clone(&TIMESLICESCHED.[<trigflag_ $taskname>]);
-                            let thread_objs = Arc::clone(&objs);
-                            let core: usize = $core;
-                            let prio: u8 = $prio;
-                            assert!(prio < 10, "prio must be a number in the range 0..=9");
-                            let stack: usize = ($stack_kib) * 1024;
-                            let name: &'static str = core::concat!(core::stringify!($name), "_cpu", $core, "\0");
-                            let name_cstr = CStr::from_bytes_with_nul(name.as_bytes()).unwrap();
-                            $crate::hal::task_spawn(
-                                name_cstr,
-                                core,
-                                prio,
-                                stack,
-                                move || {
-                                    assert_eq!($crate::hal::current_core(), core);
-                                    let (flag_mutex, trig_condvar) = &*thread_trigflag;
-                                    loop {
-                                        // Wait for the thread flag to be set.
-                                        {
-                                            let mut flag = flag_mutex.lock().unwrap();
-                                            while !*flag {
-                                                flag = trig_condvar.wait(flag).unwrap();
-                                            }
-                                            *flag = false;
+                            if [<$taskname:upper _THREAD>] == TaskId::$taskname.index() {
+                                let thread = TaskId::$taskname.index();
+                                let thread_objs = Arc::clone(&objs);
+                                let core: usize = $core;
+                                let prio: u8 = [<$taskname:upper _PRIO>];
+                                let stack: usize = [<$taskname:upper _THREAD_STACK>];
+                                let name = CStr::from_bytes_until_nul(&THREAD_NAMES[thread]).unwrap();
+                                threads.push($crate::hal::task_spawn(
+                                    name,
+                                    core,
+                                    prio,
+                                    stack,
+                                    move || {
+                                        assert_eq!($crate::hal::current_core(), core);
+                                        TIMESLICESCHED.stack_probes[thread].register(
+                                            $crate::hal::thread_stack_size(stack)
+                                        );
+                                        let _guard = TaskThreadGuard(thread);
+                                        let signal = &TIMESLICESCHED.signals[thread];
+                                        let mut state = TaskThreadState::new();
+                                        // Wait for the tasks of this thread to be released.
+                                        while let Some(pending) = signal.wait() {
+                                            TimeSliceSched::run_thread(thread, &pending, &thread_objs, &mut state);
                                         }
+                                    }
+                                ));
+                            }
+                        )*
 
-                                        let begin = TIMESLICESCHED.rt.meas_begin();
+                        // The offsets are applied by the first base tick.
+                        $(
+                            TIMESLICESCHED.[<next_offset_ticks_ $taskname>].store(
+                                ([<$taskname:upper _OFFSET_US>] / BASE_PERIOD_US) as u32,
+                                Relaxed
+                            );
+                        )*
+                        TIMESLICESCHED.offsets_changed.store(true, Release);
+                        if AUTO_OFFSETS {
+                            apply_schedule_table(&auto_schedule_table(None));
+                        }
 
-                                        // Execute all handlers for this task.
-                                        for obj in &*thread_objs {
-                                            obj.$taskname();
-                                        }
+                        TIMESLICESCHED.count.store(0, Relaxed);
+                        fence(SeqCst);
+
+                        let timer = $crate::hal::Timer::new(
+                            |release_us| TIMESLICESCHED.base_tick_handler(release_us),
+                            Duration::from_micros(BASE_PERIOD_US)
+                        );
+                        *TIMESLICESCHED_OS.lock().unwrap() = Some(TimeSliceSchedOs {
+                            timer,
+                            threads,
+                            obj_names,
+                        });
+                    }
+
+                    /// Shut down the time slice scheduler.
+                    fn shutdown() {
+                        let Some(os) = TIMESLICESCHED_OS.lock().unwrap().take() else {
+                            return;
+                        };
+
+                        // Stop the base timer. No more tasks are released after this.
+                        drop(os.timer);
+
+                        // Stop all task threads after their current activation.
+                        // The threads drop their pending activations on exit.
+                        for signal in &TIMESLICESCHED.signals {
+                            signal.stop();
+                        }
+                        for thread in os.threads {
+                            thread.join();
+                        }
+
+                        TIMESLICESCHED.initialized.store(false, SeqCst);
+                    }
+
+                    /// Run one activation of each pending task of the thread `thread`,
+                    /// in the order of definition.
+                    fn run_thread(
+                        thread: usize,
+                        pending: &[bool; TASK_COUNT],
+                        objs: &[OpsObject; $num_objs],
+                        state: &mut TaskThreadState,
+                    ) {
+                        let signal = &TIMESLICESCHED.signals[thread];
+                        $(
+                            // Do not start any more activations after a stop request.
+                            if pending[TaskId::$taskname.index()] && !signal.is_stopped() {
+                                TimeSliceSched::[<run_ $taskname>](thread, objs, state);
+                            }
+                        )*
+                    }
 
-                                        TIMESLICESCHED.rt.meas_end(core::stringify!($taskname), $core, begin);
+                    $(
+                        /// Run one pending activation of the task.
+                        fn [<run_ $taskname>](
+                            thread: usize,
+                            objs: &[OpsObject; $num_objs],
+                            state: &mut TaskThreadState,
+                        ) {
+                            let trigger = &TIMESLICESCHED.[<trigger_ $taskname>];
+                            let Some(activation) = trigger.try_start() else {
+                                return;
+                            };
+                            let policy = $crate::__timeslice_panic_policy!($($on_panic)?);
+                            let obj_enabled = &mut state.obj_enabled[TaskId::$taskname.index()];
+                            let prev_start_us = &mut state.prev_start_us[TaskId::$taskname.index()];
+                            let missed = activation.missed;
+
+                            // Report dropped releases.
+                            if missed > 0
+                                && $crate::__timeslice_overrun_policy!($($on_overrun $(($overrun_arg))?)?)
+                                    == OverrunPolicy::Callback
+                            {
+                                for (i, obj) in objs.iter().enumerate() {
+                                    if !obj_enabled[i] {
+                                        continue;
                                     }
+                                    if let Err(payload) = $crate::fault::guard(|| {
+                                        obj.sched_overrun(TaskId::$taskname, missed);
+                                    }) {
+                                        obj_enabled[i] = TimeSliceSched::handle_panic(
+                                            TaskId::$taskname, i, payload, policy
+                                        );
+                                    }
+                                }
+                            }
+
+                            let begin = TIMESLICESCHED.rt.meas_begin();
+
+                            let ctx = $crate::TaskCtx {
+                                activation: activation.number,
+                                release_us: activation.release_us,
+                                start_us: $crate::hal::now_us(),
+                                prev_start_us: *prev_start_us,
+                                period: Duration::from_micros([<$taskname:upper _PERIOD_US>]),
+                                missed,
+                            };
+                            *prev_start_us = Some(ctx.start_us);
+
+                            // Execute all handlers for this task.
+                            for (i, obj) in objs.iter().enumerate() {
+                                if !obj_enabled[i] {
+                                    continue;
                                 }
+                                let obj_begin = TIMESLICESCHED.rt.obj_meas_begin();
+                                if let Err(payload) = $crate::fault::guard(|| {
+                                    obj.[<$taskname _ctx>](&ctx);
+                                }) {
+                                    obj_enabled[i] = TimeSliceSched::handle_panic(
+                                        TaskId::$taskname, i, payload, policy
+                                    );
+                                }
+                                TIMESLICESCHED.rt.obj_meas_end(
+                                    TaskId::$taskname.index(),
+                                    i,
+                                    obj_begin
+                                );
+                            }
+
+                            // Check the execution time budget.
+                            if [<$taskname:upper _BUDGET_US>] > 0 {
+                                let runtime_us = $crate::hal::now_us().saturating_sub(ctx.start_us);
+                                if runtime_us > [<$taskname:upper _BUDGET_US>] {
+                                    TimeSliceSched::handle_budget_overrun(
+                                        TaskId::$taskname,
+                                        &TIMESLICESCHED.[<budget_overruns_ $taskname>],
+                                        runtime_us,
+                                        [<$taskname:upper _BUDGET_US>]
+                                    );
+                                }
+                            }
+
+                            TIMESLICESCHED.rt.meas_end(
+                                TaskId::$taskname.index(),
+                                $core,
+                                activation.release_us,
+                                begin
                             );
-                        )*
 
-                        TIMESLICESCHED.baseperiod.store(baseperiod, Relaxed);
-                        TIMESLICESCHED.count.store(0, Relaxed);
-                        TIMESLICESCHED.count_mod.store(count_mod, Relaxed);
-                        fence(SeqCst);
+                            if trigger.done() {
+                                // Run the next queued activation in the next round.
+                                TIMESLICESCHED.signals[thread].notify(TaskId::$taskname.index());
+                            }
+                        }
+                    )*
+
+                    /// Handle a panic of the object `obj` in a task handler.
+                    ///
+                    /// Returns whether the object shall stay enabled for this task.
+                    fn handle_panic(
+                        task: TaskId,
+                        obj: usize,
+                        payload: Box<dyn core::any::Any + Send>,
+                        policy: PanicPolicy,
+                    ) -> bool {
+                        let hook = *FAULT_HOOK.lock().unwrap();
+                        if let Some(hook) = hook {
+                            // A panicking hook must not take down the task thread.
+                            // The policy is applied in any case.
+                            let _ = $crate::fault::guard(|| hook(task, obj, &*payload));
+                        }
+                        match policy {
+                            PanicPolicy::Continue => true,
+                            PanicPolicy::Disable => false,
+                            PanicPolicy::Fault => std::process::abort(),
+                        }
+                    }
 
-                        *TIMESLICESCHED_OS.lock().unwrap() = Some($crate::hal::Timer::new(
-                            || TIMESLICESCHED.base_tick_handler(),
-                            Duration::from_millis(baseperiod as u64)
-                        ));
+                    /// Handle a task activation that exceeded its execution time budget.
+                    fn handle_budget_overrun(
+                        task: TaskId,
+                        counter: &AtomicU32,
+                        runtime_us: u64,
+                        budget_us: u64,
+                    ) {
+                        counter.fetch_add(1, Relaxed);
+                        let hook = *BUDGET_HOOK.lock().unwrap();
+                        if let Some(hook) = hook {
+                            // A panicking hook must not take down the task thread.
+                            let _ = $crate::fault::guard(|| {
+                                hook(
+                                    task,
+                                    Duration::from_micros(runtime_us),
+                                    Duration::from_micros(budget_us)
+                                );
+                            });
+                        }
                     }
 
                     /// Base timer tick handler.
-                    fn base_tick_handler(&self) {
-                        let baseperiod = self.baseperiod.load(Relaxed);
+                    ///
+                    /// `release_us` is the nominal timestamp of this tick.
+                    #[allow(clippy::modulo_one)]
+                    fn base_tick_handler(&self, release_us: u64) {
                         let count = self.count.load(Relaxed);
+
+                        // Apply changed phase offsets at the start of the hyperperiod.
+                        if count == 0 && self.offsets_changed.swap(false, Acquire) {
+                            $(
+                                self.[<offset_ticks_ $taskname>].store(
+                                    self.[<next_offset_ticks_ $taskname>].load(Relaxed),
+                                    Relaxed
+                                );
+                            )*
+                        }
+
+                        let paused = self.paused.load(Relaxed);
                         $(
-                            if count % (($timebase) / baseperiod) == 0 {
-                                let (flag, trig) = &*self.[<trigflag_ $taskname>];
-                                *flag.lock().unwrap() = true;
-                                trig.notify_one();
+                            if !paused
+                                && self.[<enabled_ $taskname>].load(Relaxed)
+                                && count as u64 % ([<$taskname:upper _PERIOD_US>] / BASE_PERIOD_US)
+                                    == self.[<offset_ticks_ $taskname>].load(Relaxed) as u64
+                            {
+                                if self.[<trigger_ $taskname>].release(
+                                    $crate::__timeslice_overrun_policy!($($on_overrun $(($overrun_arg))?)?),
+                                    release_us
+                                ) {
+                                    self.signals[[<$taskname:upper _THREAD>]].notify(TaskId::$taskname.index());
+                                }
                             }
                         )*
-                        let count_mod = self.count_mod.load(Relaxed);
-                        let count = (count + 1) % count_mod;
+                        let count = (count + 1) % HYPERPERIOD_TICKS;
                         self.count.store(count, Relaxed);
                     }
                 }
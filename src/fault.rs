@@ -0,0 +1,32 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+use core::any::Any;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+/// What to do, if a task handler of an object panics.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PanicPolicy {
+    /// Continue with the next object and call the handler again at the next activation.
+    ///
+    /// The state of the object is not reset.
+    Continue,
+    /// Do not call the handlers of the panicking object for this task anymore.
+    Disable,
+    /// Abort the process. On ESP-IDF this resets the system.
+    Fault,
+}
+
+/// Run a task handler and catch a panic.
+#[inline]
+pub fn guard<F: FnOnce()>(f: F) -> Result<(), Box<dyn Any + Send>> {
+    catch_unwind(AssertUnwindSafe(f))
+}
+
+// vim: ts=4 sw=4 expandtab
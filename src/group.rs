@@ -0,0 +1,136 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+/// Get the index of the thread that runs task `index`.
+///
+/// If `group` is true, then all tasks with the same core and priority
+/// run in the thread of the first of these tasks.
+/// Otherwise each task runs in its own thread.
+pub const fn thread(cores: &[usize], prios: &[u8], index: usize, group: bool) -> usize {
+    if !group {
+        return index;
+    }
+    let mut j = 0;
+    while j < index {
+        if cores[j] == cores[index] && prios[j] == prios[index] {
+            return j;
+        }
+        j += 1;
+    }
+    index
+}
+
+/// Get the stack size of a thread.
+///
+/// This is the maximum stack size of all tasks that run in the thread.
+pub const fn stack_size(threads: &[usize], stacks: &[usize], thread: usize) -> usize {
+    let mut size = 0;
+    let mut i = 0;
+    while i < threads.len() {
+        if threads[i] == thread && stacks[i] > size {
+            size = stacks[i];
+        }
+        i += 1;
+    }
+    size
+}
+
+/// The size of a thread name buffer, including the NUL terminator.
+pub const NAME_BUF_LEN: usize = crate::hal::MAX_THREAD_NAME_LEN + 1;
+
+/// Length of the hash suffix of a truncated thread name, including the `~` separator.
+const NAME_HASH_LEN: usize = 5;
+
+/// Build the NUL terminated thread name from `name`.
+///
+/// A name that is longer than the thread name limit of the hal is truncated
+/// and a hash of the full name is appended, so that truncated names stay distinct.
+pub const fn name(name: &str) -> [u8; NAME_BUF_LEN] {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+
+    let bytes = name.as_bytes();
+    let mut n = 0;
+    while n < bytes.len() {
+        assert!(
+            bytes[n] != 0,
+            "The thread name must not contain NUL characters"
+        );
+        n += 1;
+    }
+
+    let mut buf = [0; NAME_BUF_LEN];
+    let mut len = bytes.len();
+    let truncate = len > crate::hal::MAX_THREAD_NAME_LEN;
+    if truncate {
+        len = crate::hal::MAX_THREAD_NAME_LEN - NAME_HASH_LEN;
+        // Do not split a UTF-8 character.
+        while len > 0 && (bytes[len] & 0xC0) == 0x80 {
+            len -= 1;
+        }
+    }
+    let mut i = 0;
+    while i < len {
+        buf[i] = bytes[i];
+        i += 1;
+    }
+    if truncate {
+        // FNV-1a hash of the full name, folded to 16 bits.
+        let mut hash: u32 = 0x811C_9DC5;
+        let mut j = 0;
+        while j < bytes.len() {
+            hash = (hash ^ bytes[j] as u32).wrapping_mul(0x0100_0193);
+            j += 1;
+        }
+        let hash = (hash >> 16) ^ (hash & 0xFFFF);
+        buf[len] = b'~';
+        let mut k = 0;
+        while k < NAME_HASH_LEN - 1 {
+            buf[len + 1 + k] = HEX[((hash >> ((NAME_HASH_LEN - 2 - k) * 4)) & 0xF) as usize];
+            k += 1;
+        }
+    }
+    buf
+}
+
+/// Get the thread name from the thread name buffer `buf`.
+pub const fn name_str(buf: &[u8; NAME_BUF_LEN]) -> &str {
+    let mut len = 0;
+    while buf[len] != 0 {
+        len += 1;
+    }
+    match core::str::from_utf8(buf.split_at(len).0) {
+        Ok(name) => name,
+        Err(_) => panic!("Invalid thread name"),
+    }
+}
+
+/// Check that the name of thread `thread` is different from
+/// the names of all other threads.
+pub const fn name_is_unique(
+    names: &[[u8; NAME_BUF_LEN]],
+    threads: &[usize],
+    thread: usize,
+) -> bool {
+    let mut i = 0;
+    while i < threads.len() {
+        if threads[i] == i && i != thread {
+            let mut j = 0;
+            while j < NAME_BUF_LEN && names[i][j] == names[thread][j] {
+                j += 1;
+            }
+            if j == NAME_BUF_LEN {
+                return false;
+            }
+        }
+        i += 1;
+    }
+    true
+}
+
+// vim: ts=4 sw=4 expandtab
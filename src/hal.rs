@@ -11,6 +11,14 @@ cfg_if::cfg_if! {
     if #[cfg(feature = "hal-espidf")] {
         mod espidf;
         pub use espidf::interface::*;
+    } else if #[cfg(feature = "hal-std")] {
+        mod smaps;
+        mod std;
+        pub use self::std::interface::*;
+    } else if #[cfg(feature = "hal-sim")] {
+        mod sim;
+        mod smaps;
+        pub use sim::interface::*;
     } else if #[cfg(feature = "hal-dummy")] {
         mod dummy;
         pub use dummy::interface::*;
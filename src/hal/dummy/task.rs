@@ -7,18 +7,58 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 //
 
+#![allow(clippy::new_without_default)]
+
 use core::ffi::CStr;
 
+/// The maximum thread name length, in bytes, excluding the NUL terminator.
+pub const MAX_THREAD_NAME_LEN: usize = 15;
+
+pub struct TaskHandle {}
+
+impl TaskHandle {
+    pub fn join(self) {}
+}
+
 pub fn task_spawn<F, T>(
     _name: &'static CStr,
     _core: usize,
     _priority: u8,
     _stack_size: usize,
     _f: F,
-) where
+) -> TaskHandle
+where
     F: FnOnce() -> T + Send + 'static,
     T: Send + 'static,
 {
+    TaskHandle {}
+}
+
+#[inline(always)]
+pub fn activation_released() {}
+
+#[inline(always)]
+pub fn activation_completed() {}
+
+/// Get the actual stack size of a task thread with the requested `stack_size`.
+pub fn thread_stack_size(stack_size: usize) -> usize {
+    stack_size
+}
+
+pub struct StackProbe {}
+
+impl StackProbe {
+    pub const fn new() -> Self {
+        Self {}
+    }
+
+    pub fn register(&self, _stack_size: usize) {}
+
+    pub fn reset(&self) {}
+
+    pub fn used(&self) -> Option<usize> {
+        None
+    }
 }
 
 // vim: ts=4 sw=4 expandtab
@@ -8,7 +8,7 @@
 //
 
 #[inline]
-pub fn now_us() -> u32 {
+pub fn now_us() -> u64 {
     0
 }
 
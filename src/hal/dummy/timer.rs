@@ -9,6 +9,8 @@
 
 use core::{marker::PhantomData, time::Duration};
 
+pub const MIN_PERIOD_US: u64 = 1;
+
 pub struct Timer<'a> {
     _x: PhantomData<&'a ()>,
 }
@@ -16,10 +18,14 @@ pub struct Timer<'a> {
 impl<'a> Timer<'a> {
     pub fn new<F>(_callback: F, _period: Duration) -> Self
     where
-        F: FnMut() + Send + 'static,
+        F: FnMut(u64) + Send + 'static,
     {
         Self { _x: PhantomData }
     }
 }
 
+impl Drop for Timer<'_> {
+    fn drop(&mut self) {}
+}
+
 // vim: ts=4 sw=4 expandtab
@@ -9,9 +9,12 @@
 
 pub use crate::hal::espidf::{
     cpu::{CORES, current_core},
-    task::task_spawn,
+    task::{
+        MAX_THREAD_NAME_LEN, StackProbe, TaskHandle, activation_completed, activation_released,
+        task_spawn, thread_stack_size,
+    },
     time::now_us,
-    timer::Timer,
+    timer::{MIN_PERIOD_US, Timer},
 };
 
 // vim: ts=4 sw=4 expandtab
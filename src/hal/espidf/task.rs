@@ -7,14 +7,43 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 //
 
+#![allow(clippy::new_without_default)]
+
 use core::ffi::CStr;
 use esp_idf_hal::task::thread::ThreadSpawnConfiguration;
+use std::{
+    sync::atomic::{AtomicUsize, Ordering::Relaxed},
+    thread::JoinHandle,
+};
 
 const MAX_TASK_PRIO: u8 = 20;
 const MIN_TASK_PRIO: u8 = 5;
 
+/// The maximum thread name length, in bytes, excluding the NUL terminator.
+///
+/// This is `configMAX_TASK_NAME_LEN` minus the NUL terminator.
+pub const MAX_THREAD_NAME_LEN: usize =
+    esp_idf_hal::sys::CONFIG_FREERTOS_MAX_TASK_NAME_LEN as usize - 1;
+
+pub struct TaskHandle {
+    thread: JoinHandle<()>,
+}
+
+impl TaskHandle {
+    /// Wait for the task thread to exit.
+    pub fn join(self) {
+        let _ = self.thread.join();
+    }
+}
+
 #[allow(clippy::field_reassign_with_default)]
-pub fn task_spawn<F, T>(name: &'static CStr, core: usize, priority: u8, stack_size: usize, f: F)
+pub fn task_spawn<F, T>(
+    name: &'static CStr,
+    core: usize,
+    priority: u8,
+    stack_size: usize,
+    f: F,
+) -> TaskHandle
 where
     F: FnOnce() -> T + Send + 'static,
     T: Send + 'static,
@@ -29,14 +58,81 @@ where
     ThreadSpawnConfiguration::set(&conf).expect("Failed to set thread configuration.");
 
     // Spawn the thread.
-    std::thread::Builder::new()
+    let thread = std::thread::Builder::new()
         .name(name.to_str().unwrap().to_string())
         .stack_size(stack_size)
-        .spawn(f)
+        .spawn(move || {
+            f();
+        })
         .expect("Failed to spawn timeslice_sched thread.");
 
     ThreadSpawnConfiguration::set(&Default::default())
         .expect("Failed to set thread configuration.");
+
+    TaskHandle { thread }
+}
+
+#[inline(always)]
+pub fn activation_released() {}
+
+#[inline(always)]
+pub fn activation_completed() {}
+
+/// Get the actual stack size of a task thread with the requested `stack_size`.
+pub fn thread_stack_size(stack_size: usize) -> usize {
+    stack_size
+}
+
+/// Stack usage measurement of a task thread.
+///
+/// The usage is the FreeRTOS stack high-water mark of the task.
+pub struct StackProbe {
+    /// The FreeRTOS handle of the task. 0 if not registered.
+    handle: AtomicUsize,
+    /// The stack size of the task, in bytes.
+    stack_size: AtomicUsize,
+}
+
+impl StackProbe {
+    pub const fn new() -> Self {
+        Self {
+            handle: AtomicUsize::new(0),
+            stack_size: AtomicUsize::new(0),
+        }
+    }
+
+    /// Register the calling thread.
+    #[allow(unsafe_code)]
+    pub fn register(&self, stack_size: usize) {
+        // SAFETY: Getting the handle of the calling task has no preconditions.
+        let handle = unsafe { esp_idf_hal::sys::xTaskGetCurrentTaskHandle() };
+        self.stack_size.store(stack_size, Relaxed);
+        self.handle.store(handle as usize, Relaxed);
+    }
+
+    /// Unregister the thread.
+    pub fn reset(&self) {
+        self.handle.store(0, Relaxed);
+    }
+
+    /// Get the stack usage of the registered thread, in bytes.
+    ///
+    /// This can be called from any thread.
+    /// It must only be called while the registered thread is running.
+    #[allow(unsafe_code)]
+    pub fn used(&self) -> Option<usize> {
+        let handle = self.handle.load(Relaxed);
+        if handle == 0 {
+            return None;
+        }
+        // SAFETY: The handle belongs to a task that has not exited, yet.
+        //         The task thread resets its probe before it exits, on any exit path.
+        //         The scheduler only calls this while its task threads are running.
+        let free = unsafe {
+            esp_idf_hal::sys::uxTaskGetStackHighWaterMark(handle as esp_idf_hal::sys::TaskHandle_t)
+        };
+        Some(self.stack_size.load(Relaxed).saturating_sub(free as usize))
+    }
 }
 
 // vim: ts=4 sw=4 expandtab
@@ -7,10 +7,16 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 //
 
+use esp_idf_svc::timer::{EspTimerService, Task};
+
+/// Get the monotonic time since boot, in microseconds.
+///
+/// This is the `esp_timer_get_time()` clock.
+/// The system time (`gettimeofday`) is not used, because SNTP can step it.
 #[inline]
-pub fn now_us() -> u32 {
-    let systime = esp_idf_svc::systime::EspSystemTime;
-    systime.now().as_micros() as u32
+pub fn now_us() -> u64 {
+    let timsvc = EspTimerService::<Task>::new().expect("Failed to create system timer service.");
+    timsvc.now().as_micros() as u64
 }
 
 // vim: ts=4 sw=4 expandtab
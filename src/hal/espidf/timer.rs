@@ -7,22 +7,37 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 //
 
+use crate::hal::espidf::time::now_us;
 use core::time::Duration;
 use esp_idf_svc::timer::{EspTimer, EspTimerService, Task};
 
+/// The minimum period of a periodic ESP timer.
+pub const MIN_PERIOD_US: u64 = 50;
+
 pub struct Timer<'a> {
     _timsvc: EspTimerService<Task>,
     _tim: EspTimer<'a>,
 }
 
 impl<'a> Timer<'a> {
-    pub fn new<F>(callback: F, period: Duration) -> Self
+    /// Start a periodic timer.
+    ///
+    /// The callback receives the nominal expiry time of the tick, in microseconds.
+    /// A periodic esp_timer does not skip expiries when a callback is delayed,
+    /// therefore the nominal time is derived from the start time and the tick count.
+    pub fn new<F>(mut callback: F, period: Duration) -> Self
     where
-        F: FnMut() + Send + 'static,
+        F: FnMut(u64) + Send + 'static,
     {
+        let period_us = period.as_micros() as u64;
         let timsvc = EspTimerService::new().expect("Failed to create system timer service.");
+        let start_us = now_us();
+        let mut ticks: u64 = 0;
         let tim = timsvc
-            .timer(callback)
+            .timer(move || {
+                ticks += 1;
+                callback(start_us + ticks * period_us);
+            })
             .expect("Failed to create system timer.");
         tim.every(period).expect("Failed to start system timer.");
         Self {
@@ -0,0 +1,179 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+use core::{cell::RefCell, time::Duration};
+use std::sync::{Arc, Condvar, Mutex};
+
+/// Timer callback. The argument is the expiry time in microseconds.
+pub(super) type TimerCallback = Arc<Mutex<dyn FnMut(u64) + Send>>;
+
+/// A registered periodic simulation timer.
+struct SimTimer {
+    id: u64,
+    period_us: u64,
+    next_us: u64,
+    callback: TimerCallback,
+}
+
+/// The state of a simulated system.
+struct SimState {
+    now_us: u64,
+    next_timer_id: u64,
+    timers: Vec<SimTimer>,
+    busy: usize,
+}
+
+/// A simulated system with its own virtual clock.
+pub(super) struct Sim {
+    state: Mutex<SimState>,
+    idle: Condvar,
+}
+
+thread_local! {
+    /// The simulated system of this thread.
+    static CURRENT: RefCell<Option<Arc<Sim>>> = const { RefCell::new(None) };
+}
+
+/// Get the simulated system of the calling thread.
+///
+/// Task threads use the system of the thread that spawned them.
+/// Every other thread gets its own system on first use.
+pub(super) fn current() -> Arc<Sim> {
+    CURRENT.with(|current| {
+        Arc::clone(current.borrow_mut().get_or_insert_with(|| {
+            Arc::new(Sim {
+                state: Mutex::new(SimState {
+                    now_us: 0,
+                    next_timer_id: 0,
+                    timers: Vec::new(),
+                    busy: 0,
+                }),
+                idle: Condvar::new(),
+            })
+        }))
+    })
+}
+
+/// Make `sim` the simulated system of the calling thread.
+pub(super) fn set_current(sim: Arc<Sim>) {
+    CURRENT.with(|current| *current.borrow_mut() = Some(sim));
+}
+
+impl Sim {
+    /// Register a new periodic timer that first fires one period from now.
+    pub(super) fn timer_add(&self, callback: TimerCallback, period: Duration) -> u64 {
+        let mut sim = self.state.lock().unwrap();
+        let id = sim.next_timer_id;
+        sim.next_timer_id += 1;
+        let period_us = (period.as_micros() as u64).max(1);
+        let next_us = sim.now_us + period_us;
+        sim.timers.push(SimTimer {
+            id,
+            period_us,
+            next_us,
+            callback,
+        });
+        id
+    }
+
+    /// Unregister a timer.
+    pub(super) fn timer_remove(&self, id: u64) {
+        self.state.lock().unwrap().timers.retain(|t| t.id != id);
+    }
+
+    fn now_us(&self) -> u64 {
+        self.state.lock().unwrap().now_us
+    }
+
+    fn busy_inc(&self) {
+        self.state.lock().unwrap().busy += 1;
+    }
+
+    fn busy_dec(&self) {
+        let mut sim = self.state.lock().unwrap();
+        sim.busy = sim.busy.saturating_sub(1);
+        if sim.busy == 0 {
+            self.idle.notify_all();
+        }
+    }
+
+    fn run_until_idle(&self) {
+        let mut sim = self.state.lock().unwrap();
+        while sim.busy > 0 {
+            sim = self.idle.wait(sim).unwrap();
+        }
+    }
+
+    fn advance(&self, duration: Duration) {
+        self.run_until_idle();
+        let target_us = self.now_us() + duration.as_micros() as u64;
+        loop {
+            // Find the next expiring timer and advance the time to its expiry.
+            let (callback, now_us) = {
+                let mut sim = self.state.lock().unwrap();
+                let Some(timer) = sim
+                    .timers
+                    .iter_mut()
+                    .filter(|t| t.next_us <= target_us)
+                    .min_by_key(|t| (t.next_us, t.id))
+                else {
+                    sim.now_us = target_us;
+                    break;
+                };
+                let now_us = timer.next_us;
+                timer.next_us += timer.period_us;
+                let callback = Arc::clone(&timer.callback);
+                sim.now_us = now_us;
+                (callback, now_us)
+            };
+
+            // Fire the timer and let all triggered tasks run.
+            (callback.lock().unwrap())(now_us);
+            self.run_until_idle();
+        }
+    }
+}
+
+/// Get the current simulation time in microseconds.
+pub(super) fn now_us() -> u64 {
+    current().now_us()
+}
+
+/// A task activation has been released.
+pub(super) fn busy_inc() {
+    current().busy_inc();
+}
+
+/// A task activation has been completed.
+pub(super) fn busy_dec() {
+    current().busy_dec();
+}
+
+/// Get the current simulation time.
+pub fn now() -> Duration {
+    Duration::from_micros(now_us())
+}
+
+/// Wait until all released task activations have been completed.
+///
+/// The simulation time does not advance while waiting.
+pub fn run_until_idle() {
+    current().run_until_idle();
+}
+
+/// Advance the simulation time by `duration`.
+///
+/// All timers that expire within `duration` are fired in chronological order.
+/// After each timer tick all released task activations are run to completion
+/// before the time advances any further.
+pub fn advance(duration: Duration) {
+    current().advance(duration);
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,29 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+use std::cell::Cell;
+
+/// Maximum number of simulated CPU cores that can be assigned to tasks.
+pub const CORES: usize = 64;
+
+thread_local! {
+    /// The core that the current scheduler task thread is assigned to.
+    static TASK_CORE: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Remember the assigned core of the current task thread.
+pub(super) fn set_task_core(core: usize) {
+    TASK_CORE.set(core);
+}
+
+pub fn current_core() -> usize {
+    TASK_CORE.get()
+}
+
+// vim: ts=4 sw=4 expandtab
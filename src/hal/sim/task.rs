@@ -0,0 +1,72 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+use crate::hal::sim::{clock, cpu::set_task_core};
+use core::ffi::CStr;
+use std::thread::JoinHandle;
+
+/// Host code, especially debug builds, needs a lot more stack than embedded code.
+const MIN_STACK_SIZE: usize = 64 * 1024;
+
+/// The maximum thread name length, in bytes, excluding the NUL terminator.
+///
+/// Linux limits thread names to 16 bytes including the NUL terminator.
+pub const MAX_THREAD_NAME_LEN: usize = 15;
+
+/// Get the actual stack size of a task thread with the requested `stack_size`.
+pub fn thread_stack_size(stack_size: usize) -> usize {
+    stack_size.max(MIN_STACK_SIZE)
+}
+
+pub struct TaskHandle {
+    thread: JoinHandle<()>,
+}
+
+impl TaskHandle {
+    /// Wait for the task thread to exit.
+    pub fn join(self) {
+        let _ = self.thread.join();
+    }
+}
+
+pub fn task_spawn<F, T>(
+    name: &'static CStr,
+    core: usize,
+    _priority: u8,
+    stack_size: usize,
+    f: F,
+) -> TaskHandle
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let sim = clock::current();
+    let thread = std::thread::Builder::new()
+        .name(name.to_str().unwrap().to_string())
+        .stack_size(thread_stack_size(stack_size))
+        .spawn(move || {
+            clock::set_current(sim);
+            set_task_core(core);
+            f();
+        })
+        .expect("Failed to spawn timeslice_sched thread.");
+    TaskHandle { thread }
+}
+
+#[inline]
+pub fn activation_released() {
+    clock::busy_inc();
+}
+
+#[inline]
+pub fn activation_completed() {
+    clock::busy_dec();
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,43 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+use crate::hal::sim::clock;
+use core::{marker::PhantomData, time::Duration};
+use std::sync::{Arc, Mutex};
+
+pub const MIN_PERIOD_US: u64 = 1;
+
+pub struct Timer<'a> {
+    sim: Arc<clock::Sim>,
+    id: u64,
+    _x: PhantomData<&'a ()>,
+}
+
+impl<'a> Timer<'a> {
+    pub fn new<F>(callback: F, period: Duration) -> Self
+    where
+        F: FnMut(u64) + Send + 'static,
+    {
+        let sim = clock::current();
+        let id = sim.timer_add(Arc::new(Mutex::new(callback)), period);
+        Self {
+            sim,
+            id,
+            _x: PhantomData,
+        }
+    }
+}
+
+impl Drop for Timer<'_> {
+    fn drop(&mut self) {
+        self.sim.timer_remove(self.id);
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,80 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+#![allow(clippy::new_without_default)]
+
+use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+
+/// Best-effort stack usage measurement of a task thread.
+///
+/// The usage is the resident size of the memory mapping that contains the
+/// thread's stack, as reported by `/proc/self/smaps`.
+/// Stack pages stay resident once they have been touched,
+/// so this approximates the high-water mark.
+/// It also includes the thread control block and TLS that the
+/// C library places at the top of the stack mapping.
+pub struct StackProbe {
+    /// An address within the stack of the thread. 0 if not registered.
+    addr: AtomicUsize,
+}
+
+impl StackProbe {
+    pub const fn new() -> Self {
+        Self {
+            addr: AtomicUsize::new(0),
+        }
+    }
+
+    /// Register the calling thread.
+    pub fn register(&self, _stack_size: usize) {
+        let marker = 0_u8;
+        let sp = core::hint::black_box(&marker) as *const u8 as usize;
+        self.addr.store(sp, Relaxed);
+    }
+
+    /// Unregister the thread.
+    pub fn reset(&self) {
+        self.addr.store(0, Relaxed);
+    }
+
+    /// Get the stack usage of the registered thread, in bytes.
+    ///
+    /// This can be called from any thread, while the registered thread is running.
+    /// It reads and parses `/proc/self/smaps`,
+    /// so it must not be called from a time critical context.
+    pub fn used(&self) -> Option<usize> {
+        let addr = self.addr.load(Relaxed);
+        if addr == 0 {
+            return None;
+        }
+        let smaps = std::fs::read_to_string("/proc/self/smaps").ok()?;
+        let mut in_stack = false;
+        for line in smaps.lines() {
+            let mut fields = line.split_whitespace();
+            let Some(first) = fields.next() else {
+                continue;
+            };
+            if let Some((begin, end)) = first.split_once('-') {
+                let begin = usize::from_str_radix(begin, 16);
+                let end = usize::from_str_radix(end, 16);
+                if let (Ok(begin), Ok(end)) = (begin, end) {
+                    in_stack = (begin..end).contains(&addr);
+                    continue;
+                }
+            }
+            if in_stack && first == "Rss:" {
+                let kib: usize = fields.next()?.parse().ok()?;
+                return Some(kib * 1024);
+            }
+        }
+        None
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,17 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+mod cpu;
+mod task;
+mod time;
+mod timer;
+
+pub mod interface;
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,34 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+use nix::sched::sched_getcpu;
+use std::cell::Cell;
+
+/// Maximum number of host CPU cores that can be assigned to tasks.
+pub const CORES: usize = 64;
+
+thread_local! {
+    /// The core that the current scheduler task thread is assigned to.
+    static TASK_CORE: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Remember the assigned core of the current task thread.
+pub(super) fn set_task_core(core: usize) {
+    TASK_CORE.set(Some(core));
+}
+
+pub fn current_core() -> usize {
+    // A task thread always reports its assigned core,
+    // even if pinning to that core was not permitted.
+    TASK_CORE
+        .get()
+        .unwrap_or_else(|| sched_getcpu().unwrap_or(0))
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,21 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+pub use crate::hal::smaps::StackProbe;
+pub use crate::hal::std::{
+    cpu::{CORES, current_core},
+    task::{
+        MAX_THREAD_NAME_LEN, TaskHandle, activation_completed, activation_released, task_spawn,
+        thread_stack_size,
+    },
+    time::now_us,
+    timer::{MIN_PERIOD_US, Timer},
+};
+
+// vim: ts=4 sw=4 expandtab
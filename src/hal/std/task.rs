@@ -0,0 +1,113 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+use crate::hal::std::cpu::set_task_core;
+use core::ffi::CStr;
+use nix::{
+    sched::{CpuSet, sched_setaffinity},
+    unistd::Pid,
+};
+use std::{sync::Once, thread::JoinHandle};
+use thread_priority::{
+    RealtimeThreadSchedulePolicy, ThreadPriority, ThreadSchedulePolicy,
+    set_thread_priority_and_policy, thread_native_id,
+};
+
+/// SCHED_FIFO priority of the task with priority 0.
+const MIN_TASK_PRIO: u8 = 10;
+/// SCHED_FIFO priority of the base timer thread.
+pub(super) const TIMER_PRIO: u8 = 30;
+/// Host code, especially debug builds, needs a lot more stack than embedded code.
+const MIN_STACK_SIZE: usize = 64 * 1024;
+
+/// Pin the calling thread to `core`.
+fn pin_to_core(core: usize) {
+    static WARN: Once = Once::new();
+    let mut cpuset = CpuSet::new();
+    if cpuset
+        .set(core)
+        .and_then(|_| sched_setaffinity(Pid::from_raw(0), &cpuset))
+        .is_err()
+    {
+        WARN.call_once(|| {
+            eprintln!("timeslice: Failed to pin task to CPU {core}. Running unpinned.");
+        });
+    }
+}
+
+/// Switch the calling thread to SCHED_FIFO with the given priority.
+pub(super) fn set_fifo_priority(priority: u8) {
+    static WARN: Once = Once::new();
+    let res = priority.try_into().map_err(|_| ()).and_then(|prio| {
+        set_thread_priority_and_policy(
+            thread_native_id(),
+            ThreadPriority::Crossplatform(prio),
+            ThreadSchedulePolicy::Realtime(RealtimeThreadSchedulePolicy::Fifo),
+        )
+        .map_err(|_| ())
+    });
+    if res.is_err() {
+        WARN.call_once(|| {
+            eprintln!("timeslice: SCHED_FIFO is not permitted. Using default scheduling.");
+        });
+    }
+}
+
+/// The maximum thread name length, in bytes, excluding the NUL terminator.
+///
+/// Linux limits thread names to 16 bytes including the NUL terminator.
+pub const MAX_THREAD_NAME_LEN: usize = 15;
+
+/// Get the actual stack size of a task thread with the requested `stack_size`.
+pub fn thread_stack_size(stack_size: usize) -> usize {
+    stack_size.max(MIN_STACK_SIZE)
+}
+
+pub struct TaskHandle {
+    thread: JoinHandle<()>,
+}
+
+impl TaskHandle {
+    /// Wait for the task thread to exit.
+    pub fn join(self) {
+        let _ = self.thread.join();
+    }
+}
+
+pub fn task_spawn<F, T>(
+    name: &'static CStr,
+    core: usize,
+    priority: u8,
+    stack_size: usize,
+    f: F,
+) -> TaskHandle
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let thread = std::thread::Builder::new()
+        .name(name.to_str().unwrap().to_string())
+        .stack_size(thread_stack_size(stack_size))
+        .spawn(move || {
+            set_task_core(core);
+            pin_to_core(core);
+            set_fifo_priority(priority.saturating_add(MIN_TASK_PRIO));
+            f();
+        })
+        .expect("Failed to spawn timeslice_sched thread.");
+    TaskHandle { thread }
+}
+
+#[inline(always)]
+pub fn activation_released() {}
+
+#[inline(always)]
+pub fn activation_completed() {}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,89 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+use crate::hal::std::{
+    task::{TIMER_PRIO, set_fifo_priority},
+    time::now_us,
+};
+use core::{marker::PhantomData, time::Duration};
+use std::{
+    sync::{Arc, Condvar, Mutex},
+    thread::JoinHandle,
+};
+
+/// The base timer thread can not reliably handle shorter periods.
+pub const MIN_PERIOD_US: u64 = 100;
+
+pub struct Timer<'a> {
+    stop: Arc<(Mutex<bool>, Condvar)>,
+    thread: Option<JoinHandle<()>>,
+    _x: PhantomData<&'a ()>,
+}
+
+impl<'a> Timer<'a> {
+    /// Start a periodic timer.
+    ///
+    /// The callback receives the nominal expiry time of the tick, in microseconds.
+    /// If the timer falls behind, then the missed ticks are fired immediately
+    /// to catch up, each with its own nominal expiry time.
+    pub fn new<F>(mut callback: F, period: Duration) -> Self
+    where
+        F: FnMut(u64) + Send + 'static,
+    {
+        let period_us = (period.as_micros() as u64).max(1);
+        let stop = Arc::new((Mutex::new(false), Condvar::new()));
+        let thread_stop = Arc::clone(&stop);
+        let thread = std::thread::Builder::new()
+            .name("timeslice_timer".to_string())
+            .spawn(move || {
+                set_fifo_priority(TIMER_PRIO);
+                let (stop_mutex, stop_condvar) = &*thread_stop;
+                let mut next_us = now_us() + period_us;
+                loop {
+                    // Sleep until the next tick, or until we are stopped.
+                    {
+                        let mut stop = stop_mutex.lock().unwrap();
+                        loop {
+                            if *stop {
+                                return;
+                            }
+                            let now_us = now_us();
+                            if now_us >= next_us {
+                                break;
+                            }
+                            let timeout = Duration::from_micros(next_us - now_us);
+                            stop = stop_condvar.wait_timeout(stop, timeout).unwrap().0;
+                        }
+                    }
+
+                    callback(next_us);
+                    next_us += period_us;
+                }
+            })
+            .expect("Failed to spawn timeslice_timer thread.");
+        Self {
+            stop,
+            thread: Some(thread),
+            _x: PhantomData,
+        }
+    }
+}
+
+impl Drop for Timer<'_> {
+    fn drop(&mut self) {
+        let (stop_mutex, stop_condvar) = &*self.stop;
+        *stop_mutex.lock().unwrap() = true;
+        stop_condvar.notify_one();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,67 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+use core::time::Duration;
+
+/// The declared configuration of one task.
+///
+/// The `TASKS` array of a scheduler contains one entry per task,
+/// in the order of definition in `define_sched!`.
+///
+/// Example:
+///
+/// ```
+/// use std::time::Duration;
+///
+/// timeslice::define_sched! {
+///     name: sched_main,
+///     num_objs: 1,
+///     tasks: {
+///         { name: task_10ms, period: 10 ms, cpu: 0, prio: auto, stack: 16 kiB },
+///         { name: task_25ms, period: 25 ms, cpu: 0, prio: auto, stack: 3 kiB, wcet: 2 ms },
+///     },
+/// }
+///
+/// for task in &sched_main::TASKS {
+///     println!("{}: {:?} @ CPU {}, prio {}", task.name, task.period, task.core, task.prio);
+/// }
+///
+/// let task = sched_main::TaskId::task_25ms;
+/// assert_eq!(task.period(), Duration::from_millis(25));
+/// assert_eq!(task.prio(), 8);
+/// assert_eq!(task.stack_size(), 3 * 1024);
+/// assert_eq!(task.info().wcet, Some(Duration::from_millis(2)));
+/// assert_eq!(sched_main::base_period(), Duration::from_millis(5));
+/// assert_eq!(sched_main::hyperperiod(), Duration::from_millis(50));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TaskInfo {
+    /// The task name.
+    pub name: &'static str,
+    /// The task period.
+    pub period: Duration,
+    /// The declared phase offset.
+    ///
+    /// This is `None`, if no `offset` has been declared.
+    pub offset: Option<Duration>,
+    /// The CPU core the task runs on.
+    pub core: usize,
+    /// The task priority. A higher number is a higher priority.
+    ///
+    /// This is the assigned priority for `prio: auto`.
+    pub prio: u8,
+    /// The declared stack size, in bytes.
+    pub stack_size: usize,
+    /// The execution time budget, if declared.
+    pub budget: Option<Duration>,
+    /// The worst case execution time, if declared.
+    pub wcet: Option<Duration>,
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -7,7 +7,8 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 //
 
-#![forbid(unsafe_code)]
+#![cfg_attr(not(feature = "hal-espidf"), forbid(unsafe_code))]
+#![cfg_attr(feature = "hal-espidf", deny(unsafe_code))]
 #![allow(clippy::needless_doctest_main)]
 
 //! # Simple time slice scheduler
@@ -99,11 +100,18 @@
 //! so that you can get an idea about how loaded the CPUs are
 //! and how filled the time slices are.
 //!
+//! Besides the task runtime, the release jitter (delay from the base tick to the task start)
+//! and the response time (delay from the base tick to the task end) are measured per task.
+//! These show whether the task priorities are configured correctly.
+//!
 //! To enable measurement the crate feature `meas` has to be enabled in your
 //! `Cargo.toml` and at runtime the measurement has to be switched on with a call
 //! to `rt_enable(true)`.
 //! Then the statistics can then be printed to stdout with a call to `rt_print()`.
 //!
+//! Alternatively, `rt_stats()` returns a [stats::RtStats] snapshot of the statistics,
+//! which can be published in an application specific format.
+//!
 //! Example:
 //!
 //! ```
@@ -127,6 +135,14 @@
 //!     fn task_1000ms(&self) {
 //!         // Print the scheduler statistics to stdout:
 //!         sched_main::rt_print();
+//!
+//!         // Or get a snapshot of the statistics without starting a new measurement window:
+//!         let stats = sched_main::rt_stats(false);
+//!         for task in &stats.tasks {
+//!             if let Some(runtime) = &task.runtime {
+//!                 let _max_runtime_us = runtime.max.as_micros();
+//!             }
+//!         }
 //!     }
 //! }
 //!
@@ -141,17 +157,50 @@
 
 //! ```
 
+/// Do not access this module directly from other crates.
+#[doc(hidden)]
+pub mod fault;
+
+/// Do not access this module directly from other crates.
+#[doc(hidden)]
+pub mod group;
+
 /// Do not access this module directly from other crates.
 #[doc(hidden)]
 pub mod hal;
 
+/// Do not access this module directly from other crates.
+#[doc(hidden)]
+pub mod names;
+
 /// Do not access this module directly from other crates.
 #[doc(hidden)]
 pub mod meas;
 
+/// Do not access this module directly from other crates.
+#[doc(hidden)]
+pub mod period;
+
+/// Do not access this module directly from other crates.
+#[doc(hidden)]
+pub mod trigger;
+
 /// This module contains the main API macros.
 mod define_macro;
 
+mod ctx;
+pub use ctx::TaskCtx;
+
+mod info;
+pub use info::TaskInfo;
+
+#[cfg(feature = "hal-sim")]
+pub mod sim;
+
+pub mod analysis;
+pub mod schedule;
+pub mod stats;
+
 /// Re-exported for define_sched macro.
 #[doc(hidden)]
 pub use pastey::paste;
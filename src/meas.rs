@@ -7,9 +7,30 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 //
 
+use crate::stats::{RtPercentiles, RtTimeStats};
+
+/// Measurement results of one task.
+#[derive(Default)]
+pub struct RtTaskMeas {
+    pub count: u32,
+    pub runtime: Option<RtTimeStats>,
+    pub jitter: Option<RtTimeStats>,
+    pub response: Option<RtTimeStats>,
+    pub percentiles: Option<RtPercentiles>,
+    pub objs: Vec<RtObjMeas>,
+}
+
+/// Measurement results of one object within a task.
+pub struct RtObjMeas {
+    pub count: u32,
+    pub runtime: Option<RtTimeStats>,
+}
+
 cfg_if::cfg_if! {
     if #[cfg(feature = "meas")] {
+        mod atomic;
         mod enabled;
+        mod hist;
         pub use enabled::RuntimeMeas;
     } else {
         mod disabled;
@@ -0,0 +1,94 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Lock-free atomic microsecond values for the measurement accumulators.
+//!
+//! Targets without native 64 bit atomics (e.g. ESP32) would have to emulate them with a lock.
+//! A task that is preempted while holding that lock would block
+//! higher priority tasks on the same core.
+//! Therefore, these targets use native 32 bit atomics that saturate at `u32::MAX` microseconds.
+
+use std::sync::atomic::Ordering;
+
+cfg_if::cfg_if! {
+    if #[cfg(target_has_atomic = "64")] {
+        use std::sync::atomic::AtomicU64;
+
+        pub struct AtomicUs(AtomicU64);
+
+        impl AtomicUs {
+            pub const fn new(us: u64) -> Self {
+                Self(AtomicU64::new(us))
+            }
+
+            #[inline]
+            pub fn load(&self, order: Ordering) -> u64 {
+                self.0.load(order)
+            }
+
+            #[inline]
+            pub fn store(&self, us: u64, order: Ordering) {
+                self.0.store(us, order);
+            }
+
+            #[inline]
+            pub fn swap(&self, us: u64, order: Ordering) -> u64 {
+                self.0.swap(us, order)
+            }
+
+            #[inline]
+            pub fn fetch_add(&self, us: u64, order: Ordering) {
+                self.0.fetch_add(us, order);
+            }
+        }
+    } else {
+        use std::sync::atomic::AtomicU32;
+
+        pub struct AtomicUs(AtomicU32);
+
+        const fn saturate(us: u64) -> u32 {
+            if us > u32::MAX as u64 {
+                u32::MAX
+            } else {
+                us as u32
+            }
+        }
+
+        impl AtomicUs {
+            pub const fn new(us: u64) -> Self {
+                Self(AtomicU32::new(saturate(us)))
+            }
+
+            #[inline]
+            pub fn load(&self, order: Ordering) -> u64 {
+                self.0.load(order) as u64
+            }
+
+            #[inline]
+            pub fn store(&self, us: u64, order: Ordering) {
+                self.0.store(saturate(us), order);
+            }
+
+            #[inline]
+            pub fn swap(&self, us: u64, order: Ordering) -> u64 {
+                self.0.swap(saturate(us), order) as u64
+            }
+
+            #[inline]
+            pub fn fetch_add(&self, us: u64, order: Ordering) {
+                let us = saturate(us);
+                let _ = self
+                    .0
+                    .fetch_update(order, Ordering::Relaxed, |v| Some(v.saturating_add(us)));
+            }
+        }
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
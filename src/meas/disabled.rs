@@ -9,27 +9,43 @@
 
 #![allow(clippy::new_without_default)]
 
-pub struct RuntimeMeas {}
+use super::RtTaskMeas;
+use crate::stats::RtCpuStats;
+use core::time::Duration;
 
-impl RuntimeMeas {
+pub struct RuntimeMeas<const N: usize, const M: usize> {}
+
+impl<const N: usize, const M: usize> RuntimeMeas<N, M> {
     #[inline(always)]
     pub fn new() -> Self {
         Self {}
     }
 
     #[inline(always)]
-    pub fn meas_begin(&self) -> i32 {
-        0
+    pub fn meas_begin(&self) -> Option<u64> {
+        None
     }
 
     #[inline(always)]
-    pub fn meas_end(&self, _task_name: &'static str, _core: usize, _begin: i32) {}
+    pub fn meas_end(&self, _task: usize, _core: usize, _release_us: u64, _begin: Option<u64>) {}
+
+    #[inline(always)]
+    pub fn obj_meas_begin(&self) -> Option<u64> {
+        None
+    }
 
     #[inline(always)]
-    pub fn print_cpus(&self) {}
+    pub fn obj_meas_end(&self, _task: usize, _obj: usize, _begin: Option<u64>) {}
+
+    #[inline(always)]
+    pub fn stats_cpus(&self, _reset: bool) -> (Duration, Vec<RtCpuStats>) {
+        (Duration::ZERO, Vec::new())
+    }
 
     #[inline(always)]
-    pub fn print_task(&self, _task_name: &'static str, _time_base: usize, _core: usize) {}
+    pub fn stats_task(&self, _task: usize, _reset: bool) -> RtTaskMeas {
+        RtTaskMeas::default()
+    }
 
     #[inline(always)]
     pub fn is_enabled(&self) -> bool {
@@ -38,6 +54,14 @@ impl RuntimeMeas {
 
     #[inline(always)]
     pub fn enable(&self, _en: bool) {}
+
+    #[inline(always)]
+    pub fn objs_is_enabled(&self) -> bool {
+        false
+    }
+
+    #[inline(always)]
+    pub fn enable_objs(&self, _en: bool) {}
 }
 
 // vim: ts=4 sw=4 expandtab
@@ -9,30 +9,20 @@
 
 #![allow(clippy::new_without_default)]
 
-use std::{
-    collections::HashMap,
-    io::Write,
-    sync::{
-        RwLock,
-        atomic::{
-            AtomicBool, AtomicU32,
-            Ordering::{Acquire, Relaxed, SeqCst},
-        },
+use super::{RtObjMeas, RtTaskMeas, atomic::AtomicUs, hist::Histogram};
+use crate::stats::{RtCpuStats, RtTimeStats};
+use core::time::Duration;
+use std::sync::{
+    Mutex,
+    atomic::{
+        AtomicBool, AtomicU32,
+        Ordering::{Acquire, Relaxed, SeqCst},
     },
 };
 
-const TIMSK: u32 = 0x7FFF_FFFF;
-
-fn usfmt(us: u32) -> String {
-    if us >= 1000 {
-        format!("{} ms", us.div_ceil(1000))
-    } else {
-        format!("{} us", us)
-    }
-}
-
 struct RtCpuData {
-    cum: AtomicU32,
+    used: AtomicBool,
+    cum: AtomicUs,
     min: AtomicU32,
     max: AtomicU32,
 }
@@ -40,25 +30,33 @@ struct RtCpuData {
 impl RtCpuData {
     const fn new() -> Self {
         Self {
-            cum: AtomicU32::new(0),
+            used: AtomicBool::new(false),
+            cum: AtomicUs::new(0),
             min: AtomicU32::new(u32::MAX),
             max: AtomicU32::new(0),
         }
     }
 
     fn reset(&self) {
+        self.used.store(false, Relaxed);
         self.cum.store(0, Relaxed);
         self.min.store(u32::MAX, Relaxed);
         self.max.store(0, Relaxed);
     }
 
     #[inline]
-    fn update(&self, rt_us: u32) {
+    fn update(&self, rt_us: u64) {
+        self.used.store(true, Relaxed);
         self.cum.fetch_add(rt_us, Relaxed);
     }
 
     #[inline]
-    fn cum(&self) -> &AtomicU32 {
+    fn used(&self) -> bool {
+        self.used.load(Relaxed)
+    }
+
+    #[inline]
+    fn cum(&self) -> &AtomicUs {
         &self.cum
     }
 
@@ -73,182 +71,248 @@ impl RtCpuData {
     }
 }
 
+struct RtTimeData {
+    cum: AtomicUs,
+    min: AtomicUs,
+    max: AtomicUs,
+}
+
+impl RtTimeData {
+    const fn new() -> Self {
+        Self {
+            cum: AtomicUs::new(0),
+            min: AtomicUs::new(u64::MAX),
+            max: AtomicUs::new(0),
+        }
+    }
+
+    fn reset(&self) {
+        self.cum.store(0, Relaxed);
+        self.min.store(u64::MAX, Relaxed);
+        self.max.store(0, Relaxed);
+    }
+
+    #[inline]
+    fn update(&self, us: u64) {
+        self.cum.fetch_add(us, Relaxed);
+        self.min.store(self.min.load(Relaxed).min(us), Relaxed);
+        self.max.store(self.max.load(Relaxed).max(us), Relaxed);
+    }
+
+    fn stats(&self, count: u32, reset: bool) -> Option<RtTimeStats> {
+        let cum = if reset {
+            self.cum.swap(0, Relaxed)
+        } else {
+            self.cum.load(Relaxed)
+        };
+        let avg = cum.checked_div(count as u64)?;
+        Some(RtTimeStats {
+            avg: Duration::from_micros(avg),
+            min: Duration::from_micros(self.min.load(Relaxed)),
+            max: Duration::from_micros(self.max.load(Relaxed)),
+        })
+    }
+}
+
 struct RtTaskData {
     count: AtomicU32,
-    cum: AtomicU32,
-    min: AtomicU32,
-    max: AtomicU32,
+    runtime: RtTimeData,
+    jitter: RtTimeData,
+    response: RtTimeData,
+    hist: Histogram,
 }
 
 impl RtTaskData {
     const fn new() -> Self {
         Self {
             count: AtomicU32::new(0),
-            cum: AtomicU32::new(0),
-            min: AtomicU32::new(u32::MAX),
-            max: AtomicU32::new(0),
+            runtime: RtTimeData::new(),
+            jitter: RtTimeData::new(),
+            response: RtTimeData::new(),
+            hist: Histogram::new(),
         }
     }
 
     fn reset(&self) {
         self.count.store(0, Relaxed);
-        self.cum.store(0, Relaxed);
-        self.min.store(u32::MAX, Relaxed);
-        self.max.store(0, Relaxed);
+        self.runtime.reset();
+        self.jitter.reset();
+        self.response.reset();
+        self.hist.reset();
     }
 
     #[inline]
-    fn update(&self, rt_us: u32) {
+    fn update(&self, rt_us: u64, jitter_us: u64, response_us: u64) {
         self.count.fetch_add(1, Relaxed);
-        self.cum.fetch_add(rt_us, Relaxed);
-        self.min.store(self.min.load(Relaxed).min(rt_us), Relaxed);
-        self.max.store(self.max.load(Relaxed).max(rt_us), Relaxed);
+        self.runtime.update(rt_us);
+        self.jitter.update(jitter_us);
+        self.response.update(response_us);
+        self.hist.update(rt_us);
     }
+}
 
-    #[inline]
-    fn count(&self) -> &AtomicU32 {
-        &self.count
+struct RtObjData {
+    count: AtomicU32,
+    runtime: RtTimeData,
+}
+
+impl RtObjData {
+    const fn new() -> Self {
+        Self {
+            count: AtomicU32::new(0),
+            runtime: RtTimeData::new(),
+        }
     }
 
-    #[inline]
-    fn cum(&self) -> &AtomicU32 {
-        &self.cum
+    fn reset(&self) {
+        self.count.store(0, Relaxed);
+        self.runtime.reset();
     }
 
     #[inline]
-    fn min(&self) -> &AtomicU32 {
-        &self.min
+    fn update(&self, rt_us: u64) {
+        self.count.fetch_add(1, Relaxed);
+        self.runtime.update(rt_us);
     }
 
-    #[inline]
-    fn max(&self) -> &AtomicU32 {
-        &self.max
+    fn stats(&self, reset: bool) -> RtObjMeas {
+        let count = if reset {
+            self.count.swap(0, Relaxed)
+        } else {
+            self.count.load(Relaxed)
+        };
+        RtObjMeas {
+            count,
+            runtime: self.runtime.stats(count, reset),
+        }
     }
 }
 
-pub struct RuntimeMeas {
-    initialized: AtomicBool,
+pub struct RuntimeMeas<const N: usize, const M: usize> {
     enabled: AtomicBool,
-    print_stamp: AtomicU32,
+    objs_enabled: AtomicBool,
+    window_stamp: Mutex<u64>,
     cpus: [RtCpuData; crate::hal::CORES],
-    tasks: RwLock<HashMap<&'static str, RtTaskData>>,
+    tasks: [RtTaskData; N],
+    objs: [[RtObjData; M]; N],
 }
 
-impl RuntimeMeas {
+impl<const N: usize, const M: usize> RuntimeMeas<N, M> {
     pub fn new() -> Self {
         #[allow(clippy::declare_interior_mutable_const)]
         const RTCPUDATA_INIT: RtCpuData = RtCpuData::new();
         Self {
-            initialized: AtomicBool::new(false),
             enabled: AtomicBool::new(false),
-            print_stamp: AtomicU32::new(0),
+            objs_enabled: AtomicBool::new(false),
+            window_stamp: Mutex::new(0),
             cpus: [RTCPUDATA_INIT; crate::hal::CORES],
-            tasks: RwLock::new(HashMap::new()),
+            tasks: [const { RtTaskData::new() }; N],
+            objs: [const { [const { RtObjData::new() }; M] }; N],
         }
     }
 
     #[inline]
-    pub fn meas_begin(&self) -> i32 {
+    pub fn meas_begin(&self) -> Option<u64> {
         if self.is_enabled() {
-            (crate::hal::now_us() & TIMSK) as i32
+            Some(crate::hal::now_us())
         } else {
-            -1
+            None
         }
     }
 
     #[inline]
-    fn do_meas_end(&self, task: &RtTaskData, core: usize, rt: u32) {
+    pub fn meas_end(&self, task: usize, core: usize, release_us: u64, begin: Option<u64>) {
+        if !self.is_enabled() {
+            return;
+        }
+        let Some(begin) = begin else {
+            return;
+        };
+        let end = crate::hal::now_us();
+        let rt = end.saturating_sub(begin);
+        let jitter = begin.saturating_sub(release_us);
+        let response = end.saturating_sub(release_us);
         self.cpus[core].update(rt);
-        task.update(rt);
+        self.tasks[task].update(rt, jitter, response);
     }
 
     #[inline]
-    pub fn meas_end(&self, task_name: &'static str, core: usize, begin: i32) {
-        if !self.is_enabled() || begin < 0 {
-            return;
-        }
-        let rt = (crate::hal::now_us() & TIMSK).wrapping_sub(begin as u32) & TIMSK;
-        if !(0..10_000_000).contains(&rt) {
-            return;
-        }
-        let tasks = self.tasks.read().unwrap();
-        if let Some(task) = tasks.get(task_name) {
-            self.do_meas_end(task, core, rt);
+    pub fn obj_meas_begin(&self) -> Option<u64> {
+        if self.is_enabled() && self.objs_is_enabled() {
+            Some(crate::hal::now_us())
         } else {
-            drop(tasks);
-            {
-                let mut tasks = self.tasks.write().unwrap();
-                tasks.insert(task_name, RtTaskData::new());
-            }
-            let tasks = self.tasks.read().unwrap();
-            if let Some(task) = tasks.get(task_name) {
-                self.do_meas_end(task, core, rt);
-            }
+            None
         }
     }
 
-    pub fn print_cpus(&self) {
-        if self.is_enabled() {
-            let now = crate::hal::now_us() & TIMSK;
-            let initialized = self.initialized.swap(true, Relaxed);
-            let prev_time = if initialized {
-                self.print_stamp.load(Relaxed)
-            } else {
-                self.print_stamp.store(now, Relaxed);
-                now
-            };
-            let period = now.wrapping_sub(prev_time) & TIMSK;
-            if period >= 10_000_000 {
-                self.print_stamp.store(now, Relaxed);
-                let mut stdout = std::io::stdout().lock();
-                let _ = writeln!(stdout, "CPU stats too old: Discarded.");
-            } else if period >= 100_000 {
-                self.print_stamp.store(now, Relaxed);
-                let mut stdout = std::io::stdout().lock();
-                let _ = writeln!(stdout);
-                for cpu in 0..crate::hal::CORES {
-                    let rt_cpu = &self.cpus[cpu];
-                    let cur = rt_cpu.cum().swap(0, Relaxed);
-                    let cur = (cur * 100).div_ceil(period);
-                    let min = rt_cpu.min().load(Relaxed).min(cur);
-                    let max = rt_cpu.max().load(Relaxed).max(cur);
+    #[inline]
+    pub fn obj_meas_end(&self, task: usize, obj: usize, begin: Option<u64>) {
+        if let Some(begin) = begin {
+            let rt = crate::hal::now_us().saturating_sub(begin);
+            self.objs[task][obj].update(rt);
+        }
+    }
+
+    pub fn stats_cpus(&self, reset: bool) -> (Duration, Vec<RtCpuStats>) {
+        let mut cpus = Vec::new();
+        if !self.is_enabled() {
+            return (Duration::ZERO, cpus);
+        }
+        let now = crate::hal::now_us();
+        let mut window_stamp = self.window_stamp.lock().unwrap();
+        let window = now.saturating_sub(*window_stamp);
+        if window >= 100_000 {
+            if reset {
+                *window_stamp = now;
+            }
+            for (core, rt_cpu) in self.cpus.iter().enumerate() {
+                if !rt_cpu.used() {
+                    continue;
+                }
+                let cum = if reset {
+                    rt_cpu.cum().swap(0, Relaxed)
+                } else {
+                    rt_cpu.cum().load(Relaxed)
+                };
+                let cur = (cum * 100).div_ceil(window) as u32;
+                let min = rt_cpu.min().load(Relaxed).min(cur);
+                let max = rt_cpu.max().load(Relaxed).max(cur);
+                if reset {
                     rt_cpu.min().store(min, Relaxed);
                     rt_cpu.max().store(max, Relaxed);
-                    if min != u32::MAX {
-                        let _ = writeln!(
-                            stdout,
-                            "CPU {}: {} %; min {} %; max {} %",
-                            cpu, cur, min, max
-                        );
-                    }
                 }
+                cpus.push(RtCpuStats {
+                    core,
+                    cur,
+                    min,
+                    max,
+                });
             }
         }
+        (Duration::from_micros(window), cpus)
     }
 
-    pub fn print_task(&self, task_name: &'static str, time_base: usize, core: usize) {
-        if self.is_enabled() {
-            let mut stdout = std::io::stdout().lock();
-            let tasks = self.tasks.read().unwrap();
-            if let Some(rt_task) = tasks.get(task_name) {
-                let count = rt_task.count().swap(0, Relaxed);
-                let cum = rt_task.cum().swap(0, Relaxed);
-                let min = rt_task.min().load(Relaxed);
-                let max = rt_task.max().load(Relaxed);
-                let avg = cum.checked_div(count).unwrap_or(u32::MAX);
-                if min != u32::MAX {
-                    let _ = writeln!(
-                        stdout,
-                        "{}, {} ms @ CPU {}: {}; min {}; max {}",
-                        task_name,
-                        time_base,
-                        core,
-                        usfmt(avg),
-                        usfmt(min),
-                        usfmt(max)
-                    );
-                }
-            }
+    pub fn stats_task(&self, task: usize, reset: bool) -> RtTaskMeas {
+        if !self.is_enabled() {
+            return RtTaskMeas::default();
+        }
+        let rt_task = &self.tasks[task];
+        let count = if reset {
+            rt_task.count.swap(0, Relaxed)
+        } else {
+            rt_task.count.load(Relaxed)
+        };
+        RtTaskMeas {
+            count,
+            runtime: rt_task.runtime.stats(count, reset),
+            jitter: rt_task.jitter.stats(count, reset),
+            response: rt_task.response.stats(count, reset),
+            percentiles: rt_task.hist.percentiles(rt_task.runtime.max.load(Relaxed)),
+            objs: if self.objs_is_enabled() {
+                self.objs[task].iter().map(|obj| obj.stats(reset)).collect()
+            } else {
+                Vec::new()
+            },
         }
     }
 
@@ -256,9 +320,16 @@ impl RuntimeMeas {
         for cpu in 0..crate::hal::CORES {
             self.cpus[cpu].reset();
         }
-        for task in self.tasks.read().unwrap().values() {
+        for task in &self.tasks {
             task.reset();
         }
+        self.reset_objs();
+    }
+
+    fn reset_objs(&self) {
+        for obj in self.objs.iter().flatten() {
+            obj.reset();
+        }
     }
 
     #[inline]
@@ -270,13 +341,29 @@ impl RuntimeMeas {
         if en {
             if !self.enabled.load(Acquire) {
                 self.reset();
-                self.initialized.store(false, Relaxed);
+                *self.window_stamp.lock().unwrap() = crate::hal::now_us();
                 self.enabled.store(true, SeqCst);
             }
         } else {
             self.enabled.store(false, SeqCst);
         }
     }
+
+    #[inline]
+    pub fn objs_is_enabled(&self) -> bool {
+        self.objs_enabled.load(Relaxed)
+    }
+
+    pub fn enable_objs(&self, en: bool) {
+        if en {
+            if !self.objs_enabled.load(Acquire) {
+                self.reset_objs();
+                self.objs_enabled.store(true, SeqCst);
+            }
+        } else {
+            self.objs_enabled.store(false, SeqCst);
+        }
+    }
 }
 
 // vim: ts=4 sw=4 expandtab
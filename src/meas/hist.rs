@@ -0,0 +1,198 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+#![allow(clippy::new_without_default)]
+
+use crate::stats::RtPercentiles;
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "meas-hist")] {
+        use core::time::Duration;
+        use std::sync::atomic::{AtomicU32, Ordering::Relaxed};
+
+        /// Number of sub-buckets per power of two, as power of two.
+        const SUB_BITS: u32 = 3;
+        const SUB_COUNT: usize = 1 << SUB_BITS;
+
+        /// Number of buckets to cover the whole `u32` microsecond range.
+        const BUCKETS: usize = (u32::BITS - SUB_BITS + 1) as usize * SUB_COUNT;
+
+        /// Get the bucket index of a value.
+        ///
+        /// Values below `SUB_COUNT` have a bucket each.
+        /// Above that, each power of two is split into `SUB_COUNT` buckets.
+        #[inline]
+        fn bucket(us: u32) -> usize {
+            if (us as usize) < SUB_COUNT {
+                us as usize
+            } else {
+                let msb = u32::BITS - 1 - us.leading_zeros();
+                let sub = (us >> (msb - SUB_BITS)) as usize & (SUB_COUNT - 1);
+                (msb - SUB_BITS + 1) as usize * SUB_COUNT + sub
+            }
+        }
+
+        /// Get the largest value that falls into a bucket.
+        fn bucket_upper(index: usize) -> u64 {
+            if index < SUB_COUNT {
+                index as u64
+            } else {
+                let shift = (index / SUB_COUNT) as u32 - 1;
+                let sub = (index % SUB_COUNT) as u64;
+                ((SUB_COUNT as u64 + sub + 1) << shift) - 1
+            }
+        }
+
+        /// Log-scaled histogram of a time measurement.
+        pub struct Histogram {
+            buckets: [AtomicU32; BUCKETS],
+        }
+
+        impl Histogram {
+            pub const fn new() -> Self {
+                #[allow(clippy::declare_interior_mutable_const)]
+                const BUCKET_INIT: AtomicU32 = AtomicU32::new(0);
+                Self {
+                    buckets: [BUCKET_INIT; BUCKETS],
+                }
+            }
+
+            pub fn reset(&self) {
+                for bucket in &self.buckets {
+                    bucket.store(0, Relaxed);
+                }
+            }
+
+            #[inline]
+            pub fn update(&self, us: u64) {
+                let us = us.min(u32::MAX as u64) as u32;
+                self.buckets[bucket(us)].fetch_add(1, Relaxed);
+            }
+
+            /// Get the upper bound of the bucket that contains the `permille` quantile.
+            fn quantile(&self, counts: &[u32; BUCKETS], total: u64, permille: u64) -> u64 {
+                let rank = (total * permille).div_ceil(1000).max(1);
+                let mut cum = 0;
+                for (index, count) in counts.iter().enumerate() {
+                    cum += *count as u64;
+                    if cum >= rank {
+                        return bucket_upper(index);
+                    }
+                }
+                bucket_upper(BUCKETS - 1)
+            }
+
+            /// Calculate the percentiles.
+            ///
+            /// The percentiles are limited to `max_us`.
+            pub fn percentiles(&self, max_us: u64) -> Option<RtPercentiles> {
+                let counts: [u32; BUCKETS] = core::array::from_fn(|i| self.buckets[i].load(Relaxed));
+                let total: u64 = counts.iter().map(|c| *c as u64).sum();
+                if total == 0 {
+                    return None;
+                }
+                let q = |permille| {
+                    Duration::from_micros(self.quantile(&counts, total, permille).min(max_us))
+                };
+                Some(RtPercentiles {
+                    p50: q(500),
+                    p90: q(900),
+                    p99: q(990),
+                    p999: q(999),
+                })
+            }
+        }
+    } else {
+        /// Dummy histogram.
+        pub struct Histogram {}
+
+        impl Histogram {
+            #[inline(always)]
+            pub const fn new() -> Self {
+                Self {}
+            }
+
+            #[inline(always)]
+            pub fn reset(&self) {}
+
+            #[inline(always)]
+            pub fn update(&self, _us: u64) {}
+
+            #[inline(always)]
+            pub fn percentiles(&self, _max_us: u64) -> Option<RtPercentiles> {
+                None
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "meas-hist"))]
+mod tests {
+    use super::*;
+
+    const EDGES: [u32; 6] = [0, 7, 8, 15, 16, u32::MAX];
+
+    fn load_counts(hist: &Histogram) -> [u32; BUCKETS] {
+        core::array::from_fn(|i| hist.buckets[i].load(Relaxed))
+    }
+
+    #[test]
+    fn test_bucket() {
+        let buckets = EDGES.map(bucket);
+        assert_eq!(buckets, [0, 7, 8, 15, 16, BUCKETS - 1]);
+    }
+
+    #[test]
+    fn test_bucket_upper() {
+        assert_eq!(bucket_upper(0), 0);
+        assert_eq!(bucket_upper(7), 7);
+        assert_eq!(bucket_upper(8), 8);
+        assert_eq!(bucket_upper(15), 15);
+        assert_eq!(bucket_upper(16), 17);
+        assert_eq!(bucket_upper(BUCKETS - 1), u32::MAX as u64);
+    }
+
+    #[test]
+    fn test_bucket_bounds() {
+        let powers = (0..u32::BITS).flat_map(|bit| {
+            let pow = 1_u32 << bit;
+            [pow - 1, pow, pow + 1]
+        });
+        for us in (0..=4096).chain(powers).chain(EDGES) {
+            let index = bucket(us);
+            assert!(index < BUCKETS);
+            assert!(us as u64 <= bucket_upper(index), "{us}");
+            if index > 0 {
+                assert!(us as u64 > bucket_upper(index - 1), "{us}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_quantile() {
+        let hist = Histogram::new();
+        for us in EDGES {
+            hist.update(us as u64);
+        }
+        let counts = load_counts(&hist);
+        assert_eq!(hist.quantile(&counts, 6, 0), 0);
+        assert_eq!(hist.quantile(&counts, 6, 500), 8);
+        assert_eq!(hist.quantile(&counts, 6, 999), u32::MAX as u64);
+        assert_eq!(hist.quantile(&counts, 6, 1000), u32::MAX as u64);
+
+        // Values above the `u32` range fall into the last bucket.
+        hist.reset();
+        hist.update(u64::MAX);
+        assert_eq!(load_counts(&hist)[BUCKETS - 1], 1);
+        let p = hist.percentiles(1000).unwrap();
+        assert_eq!(p.p50, Duration::from_micros(1000));
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,72 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+/// Names of the provided `Ops` methods that are not task methods.
+const OPS_HOOKS: &[&str] = &["sched_overrun", "sched_obj_name"];
+
+/// Compare two strings.
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Check whether `name` is `base` with the suffix `_ctx`.
+const fn is_ctx_of(name: &str, base: &str) -> bool {
+    let (name, base) = (name.as_bytes(), base.as_bytes());
+    const SUFFIX: &[u8] = b"_ctx";
+    if name.len() != base.len() + SUFFIX.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < name.len() {
+        let expected = if i < base.len() {
+            base[i]
+        } else {
+            SUFFIX[i - base.len()]
+        };
+        if name[i] != expected {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Check that the `Ops` methods of task `index` do not clash with
+/// the methods of other tasks or with the provided `Ops` methods.
+pub const fn ops_is_unique(names: &[&str], index: usize) -> bool {
+    let name = names[index];
+    let mut i = 0;
+    while i < OPS_HOOKS.len() {
+        if str_eq(name, OPS_HOOKS[i]) {
+            return false;
+        }
+        i += 1;
+    }
+    let mut j = 0;
+    while j < names.len() {
+        if is_ctx_of(name, names[j]) {
+            return false;
+        }
+        j += 1;
+    }
+    true
+}
+
+// vim: ts=4 sw=4 expandtab
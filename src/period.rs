@@ -0,0 +1,118 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+/// Greatest common divisor.
+pub const fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple.
+///
+/// Returns `None` on overflow.
+pub const fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Convert a period to microseconds.
+pub const fn to_us(value: u64, unit_to_us: u64) -> u64 {
+    match value.checked_mul(unit_to_us) {
+        Some(us) => us,
+        None => panic!("Task period overflows"),
+    }
+}
+
+/// Calculate the base tick period of a set of task periods and phase offsets.
+///
+/// This is the greatest common divisor of all periods and all non-zero offsets.
+pub const fn base_period(periods: &[u64], offsets: &[u64]) -> u64 {
+    let mut base = 0;
+    let mut i = 0;
+    while i < periods.len() {
+        assert!(periods[i] != 0, "Task periods must not be zero");
+        base = gcd(base, periods[i]);
+        i += 1;
+    }
+    let mut i = 0;
+    while i < offsets.len() {
+        base = gcd(base, offsets[i]);
+        i += 1;
+    }
+    base
+}
+
+/// Calculate the hyperperiod of a set of task periods.
+///
+/// This is the least common multiple of all periods.
+/// After one hyperperiod the task release pattern repeats.
+pub const fn hyperperiod(periods: &[u64]) -> u64 {
+    let mut hyper = 1;
+    let mut i = 0;
+    while i < periods.len() {
+        assert!(periods[i] != 0, "Task periods must not be zero");
+        hyper = match lcm(hyper, periods[i]) {
+            Some(hyper) => hyper,
+            None => panic!("The hyperperiod (LCM) of the task periods overflows"),
+        };
+        i += 1;
+    }
+    hyper
+}
+
+/// Derive the rate monotonic priority of task `index`.
+///
+/// The task with the shortest period on a core gets priority `max_prio`.
+/// Each longer distinct period on the same core gets the next lower priority,
+/// down to 0.
+pub const fn rm_prio(periods: &[u64], cores: &[usize], index: usize, max_prio: u8) -> u8 {
+    let mut rank = 0_usize;
+    let mut j = 0;
+    while j < periods.len() {
+        if cores[j] == cores[index] && periods[j] < periods[index] {
+            // Count each distinct period only once.
+            let mut first = true;
+            let mut k = 0;
+            while k < j {
+                if cores[k] == cores[j] && periods[k] == periods[j] {
+                    first = false;
+                }
+                k += 1;
+            }
+            if first {
+                rank += 1;
+            }
+        }
+        j += 1;
+    }
+    if rank > max_prio as usize {
+        0
+    } else {
+        max_prio - rank as u8
+    }
+}
+
+/// Check whether task `index` has a lower priority than a task
+/// with a longer period on the same core.
+pub const fn rm_inverted(periods: &[u64], cores: &[usize], prios: &[u8], index: usize) -> bool {
+    let mut j = 0;
+    while j < periods.len() {
+        if cores[j] == cores[index] && periods[j] > periods[index] && prios[j] > prios[index] {
+            return true;
+        }
+        j += 1;
+    }
+    false
+}
+
+// vim: ts=4 sw=4 expandtab
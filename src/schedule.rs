@@ -0,0 +1,210 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! # Schedule table
+//!
+//! The schedule table describes when the tasks are released within one hyperperiod.
+//! All times are in base ticks.
+//!
+//! The generated `schedule_table()` function of a scheduler returns the active table.
+//! `auto_schedule_table()` returns a table with automatically assigned phase offsets,
+//! that can be applied with `apply_schedule_table()`.
+//!
+//! Example:
+//!
+//! ```
+//! use timeslice::schedule::{ScheduleEntry, ScheduleTable};
+//! use std::time::Duration;
+//!
+//! let mut table = ScheduleTable {
+//!     base_period: Duration::from_millis(10),
+//!     hyperperiod_ticks: 10,
+//!     tasks: vec![
+//!         ScheduleEntry::new("task_10ms", 0, 1, 0, false, 1),
+//!         ScheduleEntry::new("task_50ms", 0, 5, 0, false, 1),
+//!         ScheduleEntry::new("task_100ms", 0, 10, 0, false, 1),
+//!     ],
+//! };
+//! assert_eq!(table.peak_load(0), 3);
+//!
+//! table.assign_offsets();
+//! assert_eq!(table.peak_load(0), 2);
+//! ```
+
+use core::{fmt, time::Duration};
+use std::collections::BTreeSet;
+
+/// The maximum number of base ticks per hyperperiod for `auto_offsets: true`.
+///
+/// The offset assignment allocates one load counter per base tick of the hyperperiod.
+pub const MAX_AUTO_OFFSETS_TICKS: u64 = 4096;
+
+/// One task in the schedule table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScheduleEntry {
+    /// The task name.
+    pub name: &'static str,
+    /// The CPU core the task runs on.
+    pub core: usize,
+    /// The task period, in base ticks.
+    pub period_ticks: u64,
+    /// The task phase offset, in base ticks.
+    pub offset_ticks: u64,
+    /// The offset is fixed and will not be changed by [ScheduleTable::assign_offsets].
+    pub fixed: bool,
+    /// The load of one activation of this task.
+    /// This is 1 for counting releases, or e.g. the task runtime in microseconds.
+    pub weight: u64,
+}
+
+impl ScheduleEntry {
+    /// Create a new schedule table entry.
+    pub fn new(
+        name: &'static str,
+        core: usize,
+        period_ticks: u64,
+        offset_ticks: u64,
+        fixed: bool,
+        weight: u64,
+    ) -> Self {
+        Self {
+            name,
+            core,
+            period_ticks,
+            offset_ticks,
+            fixed,
+            weight,
+        }
+    }
+
+    /// Check whether the task is released at base tick `tick`.
+    #[inline]
+    pub fn is_released(&self, tick: u64) -> bool {
+        tick % self.period_ticks == self.offset_ticks
+    }
+}
+
+/// The release schedule of all tasks within one hyperperiod.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScheduleTable {
+    /// The base tick period.
+    pub base_period: Duration,
+    /// The hyperperiod, in base ticks.
+    pub hyperperiod_ticks: u64,
+    /// All tasks, in declaration order.
+    pub tasks: Vec<ScheduleEntry>,
+}
+
+impl ScheduleTable {
+    /// Get all CPU cores that run tasks.
+    pub fn cores(&self) -> Vec<usize> {
+        let cores: BTreeSet<usize> = self.tasks.iter().map(|t| t.core).collect();
+        cores.into_iter().collect()
+    }
+
+    /// Get the load of each base tick of the hyperperiod on CPU `core`.
+    pub fn load(&self, core: usize) -> Vec<u64> {
+        let mut load = vec![0; self.hyperperiod_ticks as usize];
+        for task in self.tasks.iter().filter(|t| t.core == core) {
+            add_load(&mut load, task);
+        }
+        load
+    }
+
+    /// Get the maximum load of all base ticks on CPU `core`.
+    pub fn peak_load(&self, core: usize) -> u64 {
+        (0..self.hyperperiod_ticks)
+            .map(|tick| {
+                self.tasks
+                    .iter()
+                    .filter(|t| t.core == core && t.is_released(tick))
+                    .map(|t| t.weight)
+                    .sum()
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Assign the phase offsets of all tasks, that do not have a fixed offset,
+    /// so that the peak load per base tick is minimized on each CPU core.
+    ///
+    /// The tasks are placed one by one, shortest period and heaviest weight first.
+    /// Each task is placed at the offset with the lowest resulting peak load.
+    ///
+    /// Panics, if the hyperperiod has more than [MAX_AUTO_OFFSETS_TICKS] base ticks.
+    pub fn assign_offsets(&mut self) {
+        assert!(
+            self.hyperperiod_ticks <= MAX_AUTO_OFFSETS_TICKS,
+            "assign_offsets: The hyperperiod has more than {MAX_AUTO_OFFSETS_TICKS} base ticks"
+        );
+        for core in self.cores() {
+            let mut load = vec![0; self.hyperperiod_ticks as usize];
+            for task in self.tasks.iter().filter(|t| t.core == core && t.fixed) {
+                add_load(&mut load, task);
+            }
+
+            let mut order: Vec<usize> = (0..self.tasks.len())
+                .filter(|&i| self.tasks[i].core == core && !self.tasks[i].fixed)
+                .collect();
+            order.sort_by_key(|&i| {
+                let task = &self.tasks[i];
+                (task.period_ticks, u64::MAX - task.weight)
+            });
+
+            for i in order {
+                let task = &mut self.tasks[i];
+                let mut best = (u64::MAX, u64::MAX, 0);
+                for offset in 0..task.period_ticks {
+                    let ticks =
+                        (offset..self.hyperperiod_ticks).step_by(task.period_ticks as usize);
+                    let peak = ticks.clone().map(|t| load[t as usize]).max().unwrap_or(0);
+                    let sum = ticks.map(|t| load[t as usize]).sum();
+                    best = best.min((peak, sum, offset));
+                }
+                task.offset_ticks = best.2;
+                add_load(&mut load, task);
+            }
+        }
+    }
+}
+
+fn add_load(load: &mut [u64], task: &ScheduleEntry) {
+    let ticks = (task.offset_ticks as usize..load.len()).step_by(task.period_ticks as usize);
+    for tick in ticks {
+        load[tick] += task.weight;
+    }
+}
+
+impl fmt::Display for ScheduleTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Base period {} us; hyperperiod {} ticks",
+            self.base_period.as_micros(),
+            self.hyperperiod_ticks
+        )?;
+        for task in &self.tasks {
+            writeln!(
+                f,
+                "{} @ CPU {}: period {} ticks; offset {} ticks{}",
+                task.name,
+                task.core,
+                task.period_ticks,
+                task.offset_ticks,
+                if task.fixed { " (fixed)" } else { "" }
+            )?;
+        }
+        for core in self.cores() {
+            writeln!(f, "CPU {}: peak load {}", core, self.peak_load(core))?;
+        }
+        Ok(())
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
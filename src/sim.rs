@@ -0,0 +1,77 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! # Deterministic virtual time simulation
+//!
+//! With the `hal-sim` backend the time does not advance on its own.
+//! The base timer, `now_us()` and the task execution are driven by the
+//! functions in this module instead.
+//!
+//! [advance] advances the virtual time tick by tick and runs all tasks
+//! triggered by a tick to completion before advancing any further.
+//! The virtual time stands still while tasks are running.
+//!
+//! Each thread has its own virtual clock, which is shared with the task threads
+//! of the schedulers that it initializes.
+//! Therefore, tests that run in parallel do not advance each other's time.
+//! A scheduler defined by `define_sched!` is a global object, though.
+//! It can only be initialized by one test at a time.
+//! Tests that use the same scheduler must be serialized (e.g. with a `Mutex`)
+//! and must `shutdown()` the scheduler at their end.
+//! Initializing a scheduler that is already initialized panics.
+//!
+//! Example:
+//!
+//! ```
+//! use std::{
+//!     sync::{Arc, atomic::{AtomicU32, Ordering::Relaxed}},
+//!     time::Duration,
+//! };
+//!
+//! timeslice::define_sched! {
+//!     name: sched_main,
+//!     num_objs: 1,
+//!     tasks: {
+//!         { name: task_10ms,  period: 10 ms,  cpu: 0, prio: 9, stack: 8 kiB },
+//!         { name: task_100ms, period: 100 ms, cpu: 1, prio: 8, stack: 8 kiB },
+//!     },
+//! }
+//!
+//! #[derive(Default)]
+//! struct MyThing {
+//!     count_10ms: AtomicU32,
+//!     count_100ms: AtomicU32,
+//! }
+//!
+//! impl sched_main::Ops for MyThing {
+//!     fn task_10ms(&self) {
+//!         self.count_10ms.fetch_add(1, Relaxed);
+//!     }
+//!
+//!     fn task_100ms(&self) {
+//!         self.count_100ms.fetch_add(1, Relaxed);
+//!     }
+//! }
+//!
+//! let thing = Arc::new(MyThing::default());
+//! sched_main::init([Arc::clone(&thing) as _]);
+//!
+//! timeslice::sim::advance(Duration::from_secs(1));
+//!
+//! assert_eq!(timeslice::sim::now(), Duration::from_secs(1));
+//! assert_eq!(thing.count_10ms.load(Relaxed), 100);
+//! assert_eq!(thing.count_100ms.load(Relaxed), 10);
+//!
+//! // Shut down the scheduler, so that the next test case can initialize it again.
+//! sched_main::shutdown();
+//! ```
+
+pub use crate::hal::{advance, now, run_until_idle};
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,257 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! # Runtime statistics
+//!
+//! A snapshot of the scheduler runtime statistics can be taken with
+//! the generated `rt_stats()` function of a scheduler.
+//!
+//! The `Display` implementation of [RtStats] produces the same output as `rt_print()`.
+
+use core::{fmt, time::Duration};
+
+fn durfmt(d: Duration) -> String {
+    let us = d.as_micros();
+    if us >= 1000 {
+        format!("{} ms", us.div_ceil(1000))
+    } else {
+        format!("{} us", us)
+    }
+}
+
+/// Format an exact duration, in ms if it is a whole number of milliseconds.
+pub(crate) fn periodfmt(d: Duration) -> String {
+    let us = d.as_micros();
+    if us >= 1000 && us.is_multiple_of(1000) {
+        format!("{} ms", us / 1000)
+    } else {
+        format!("{} us", us)
+    }
+}
+
+/// Average, minimum and maximum of a time measurement.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RtTimeStats {
+    /// Average time.
+    pub avg: Duration,
+    /// Minimum time.
+    pub min: Duration,
+    /// Maximum time.
+    pub max: Duration,
+}
+
+/// Percentiles of a time measurement.
+///
+/// The percentiles are taken from a log-scaled histogram.
+/// Each value is the upper bound of the histogram bucket that contains the percentile,
+/// which overestimates the percentile by at most 12.5 %.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RtPercentiles {
+    /// 50th percentile (median).
+    pub p50: Duration,
+    /// 90th percentile.
+    pub p90: Duration,
+    /// 99th percentile.
+    pub p99: Duration,
+    /// 99.9th percentile.
+    pub p999: Duration,
+}
+
+/// CPU load statistics of one CPU core.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RtCpuStats {
+    /// The CPU core number.
+    pub core: usize,
+    /// The load in the current measurement window, in percent.
+    pub cur: u32,
+    /// The minimum load of all measurement windows, in percent.
+    pub min: u32,
+    /// The maximum load of all measurement windows, in percent.
+    pub max: u32,
+}
+
+/// Runtime statistics of one object within a task.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RtObjStats {
+    /// The index of the object in the array passed to `init()`.
+    pub index: usize,
+    /// The object name returned by `Ops::sched_obj_name()`.
+    pub name: Option<&'static str>,
+    /// The number of object calls in the current measurement window.
+    pub count: u32,
+    /// The runtime of the object's task method.
+    ///
+    /// Same calculation rules as for the task runtime.
+    pub runtime: Option<RtTimeStats>,
+}
+
+/// Statistics of one task.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RtTaskStats {
+    /// The task name.
+    pub name: &'static str,
+    /// The task period.
+    pub period: Duration,
+    /// The CPU core the task runs on.
+    pub core: usize,
+    /// The number of task activations in the current measurement window.
+    pub count: u32,
+    /// The task runtime.
+    ///
+    /// The average is calculated over the current measurement window.
+    /// The minimum and maximum are calculated since the measurement has been enabled.
+    /// This is `None`, if no runtime has been measured in the current measurement window.
+    pub runtime: Option<RtTimeStats>,
+    /// The task release jitter.
+    ///
+    /// This is the delay between the nominal release of the task by the base tick
+    /// and the actual start of the task activation.
+    /// Same calculation rules as for `runtime`.
+    pub jitter: Option<RtTimeStats>,
+    /// The task response time.
+    ///
+    /// This is the time between the nominal release of the task by the base tick
+    /// and the end of the task activation.
+    /// Same calculation rules as for `runtime`.
+    pub response: Option<RtTimeStats>,
+    /// The task runtime percentiles.
+    ///
+    /// The percentiles are calculated since the measurement has been enabled.
+    /// This is `None`, if the `meas-hist` feature is not enabled
+    /// or if no runtime has been measured.
+    pub percentiles: Option<RtPercentiles>,
+    /// The runtime statistics of each object.
+    ///
+    /// This is empty, if the per-object measurement is not enabled.
+    pub objs: Vec<RtObjStats>,
+    /// The stack size of the thread that runs the task, in bytes.
+    ///
+    /// This is larger than the configured task stack size,
+    /// if the hal raises the stack size of its threads (`hal-std`, `hal-sim`)
+    /// or if the thread runs a group of tasks.
+    pub stack_size: usize,
+    /// The used stack (high-water mark) of the thread that runs the task, in bytes.
+    ///
+    /// The stack usage is measured when the statistics snapshot is taken.
+    /// This is `None`, if the hal does not support stack usage measurement
+    /// or if the scheduler is not running.
+    pub stack_used: Option<usize>,
+    /// The execution time budget of the task.
+    ///
+    /// This is `None`, if the task has no budget.
+    pub budget: Option<Duration>,
+    /// The total number of activations that exceeded the execution time budget
+    /// since the scheduler has been initialized.
+    pub budget_overruns: u32,
+    /// The total number of task overruns since the scheduler has been initialized.
+    pub overruns: u32,
+}
+
+/// Snapshot of the scheduler runtime statistics.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RtStats {
+    /// The length of the current measurement window.
+    pub window: Duration,
+    /// The CPU load of all CPU cores that run tasks.
+    ///
+    /// This is empty, if the measurement window is shorter than 100 ms.
+    pub cpus: Vec<RtCpuStats>,
+    /// The statistics of all tasks.
+    pub tasks: Vec<RtTaskStats>,
+}
+
+impl fmt::Display for RtStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.cpus.is_empty() {
+            writeln!(f)?;
+        }
+        for cpu in &self.cpus {
+            writeln!(
+                f,
+                "CPU {}: {} %; min {} %; max {} %",
+                cpu.core, cpu.cur, cpu.min, cpu.max
+            )?;
+        }
+        for task in &self.tasks {
+            if let Some(runtime) = &task.runtime {
+                write!(
+                    f,
+                    "{}, {} @ CPU {}: {}; min {}; max {}",
+                    task.name,
+                    periodfmt(task.period),
+                    task.core,
+                    durfmt(runtime.avg),
+                    durfmt(runtime.min),
+                    durfmt(runtime.max)
+                )?;
+                if let Some(jitter) = &task.jitter {
+                    write!(
+                        f,
+                        "; jitter {}, min {}, max {}",
+                        durfmt(jitter.avg),
+                        durfmt(jitter.min),
+                        durfmt(jitter.max)
+                    )?;
+                }
+                if let Some(response) = &task.response {
+                    write!(
+                        f,
+                        "; response {}, min {}, max {}",
+                        durfmt(response.avg),
+                        durfmt(response.min),
+                        durfmt(response.max)
+                    )?;
+                }
+                if let Some(percentiles) = &task.percentiles {
+                    write!(
+                        f,
+                        "; p50 {}, p90 {}, p99 {}, p99.9 {}",
+                        durfmt(percentiles.p50),
+                        durfmt(percentiles.p90),
+                        durfmt(percentiles.p99),
+                        durfmt(percentiles.p999)
+                    )?;
+                }
+                if let Some(stack_used) = task.stack_used {
+                    write!(
+                        f,
+                        "; stack {}/{} kiB",
+                        stack_used.div_ceil(1024),
+                        task.stack_size.div_ceil(1024)
+                    )?;
+                }
+                if task.overruns > 0 {
+                    write!(f, "; overruns {}", task.overruns)?;
+                }
+                if task.budget_overruns > 0 {
+                    write!(f, "; budget overruns {}", task.budget_overruns)?;
+                }
+                writeln!(f)?;
+                for obj in &task.objs {
+                    if let Some(runtime) = &obj.runtime {
+                        match obj.name {
+                            Some(name) => write!(f, "    {}", name)?,
+                            None => write!(f, "    object {}", obj.index)?,
+                        }
+                        writeln!(
+                            f,
+                            ": {}; min {}; max {}",
+                            durfmt(runtime.avg),
+                            durfmt(runtime.min),
+                            durfmt(runtime.max)
+                        )?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
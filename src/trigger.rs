@@ -0,0 +1,374 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2023-2026 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+#![allow(clippy::new_without_default)]
+
+use std::sync::{
+    Condvar, Mutex,
+    atomic::{AtomicU32, Ordering::Relaxed},
+};
+
+/// What to do, if a task is released while its previous activation
+/// has not been completed, yet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverrunPolicy {
+    /// Drop the new release.
+    Skip,
+    /// Queue the new release, if less than this number of releases are pending.
+    /// Otherwise drop it.
+    Queue(u32),
+    /// Drop the new release and report the dropped releases
+    /// to the `Ops::sched_overrun` hook before the next activation.
+    Callback,
+}
+
+/// A started task activation.
+pub struct Activation {
+    /// The activation counter.
+    pub number: u64,
+    /// The nominal release timestamp, in microseconds.
+    pub release_us: u64,
+    /// Number of dropped releases since the last activation start.
+    pub missed: u32,
+}
+
+struct TriggerState {
+    /// Number of released, but not yet started activations.
+    pending: u32,
+    /// Nominal release timestamp of the oldest pending activation.
+    release_us: u64,
+    /// Number of started activations.
+    activations: u64,
+    /// An activation is currently running.
+    running: bool,
+    /// Number of dropped releases since the last activation start.
+    missed: u32,
+}
+
+/// Task activation trigger.
+pub struct Trigger {
+    period_us: u64,
+    state: Mutex<TriggerState>,
+    overruns: AtomicU32,
+}
+
+impl Trigger {
+    pub const fn new(period_us: u64) -> Self {
+        Self {
+            period_us,
+            state: Mutex::new(TriggerState {
+                pending: 0,
+                release_us: 0,
+                activations: 0,
+                running: false,
+                missed: 0,
+            }),
+            overruns: AtomicU32::new(0),
+        }
+    }
+
+    /// Release a new task activation with the nominal release timestamp `release_us`.
+    ///
+    /// Returns `true`, if the activation has been queued
+    /// and the task thread has to be signalled.
+    pub fn release(&self, policy: OverrunPolicy, release_us: u64) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let busy = state.pending > 0 || state.running;
+        if busy {
+            self.overruns.fetch_add(1, Relaxed);
+        }
+        let queue = match policy {
+            _ if !busy => true,
+            OverrunPolicy::Skip | OverrunPolicy::Callback => false,
+            OverrunPolicy::Queue(max_pending) => state.pending < max_pending,
+        };
+        if queue {
+            if state.pending == 0 {
+                state.release_us = release_us;
+            }
+            state.pending += 1;
+            crate::hal::activation_released();
+        } else {
+            state.missed = state.missed.saturating_add(1);
+        }
+        queue
+    }
+
+    /// Reset the trigger to its initial state.
+    pub fn reset(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.pending = 0;
+        state.release_us = 0;
+        state.activations = 0;
+        state.running = false;
+        state.missed = 0;
+        self.overruns.store(0, Relaxed);
+    }
+
+    /// Drop all pending activations.
+    pub fn drop_pending(&self) {
+        let mut state = self.state.lock().unwrap();
+        for _ in 0..state.pending {
+            crate::hal::activation_completed();
+        }
+        state.pending = 0;
+    }
+
+    /// Start the next pending task activation.
+    ///
+    /// Returns `None`, if no activation is pending.
+    pub fn try_start(&self) -> Option<Activation> {
+        let mut state = self.state.lock().unwrap();
+        if state.pending == 0 {
+            return None;
+        }
+        let activation = Activation {
+            number: state.activations,
+            release_us: state.release_us,
+            missed: core::mem::take(&mut state.missed),
+        };
+        state.activations += 1;
+        state.pending -= 1;
+        if state.pending > 0 {
+            // The next queued activation has been released one period later.
+            state.release_us += self.period_us;
+        }
+        state.running = true;
+        Some(activation)
+    }
+
+    /// Mark the running task activation as completed.
+    ///
+    /// Returns `true`, if more activations are pending.
+    pub fn done(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        state.running = false;
+        crate::hal::activation_completed();
+        state.pending > 0
+    }
+
+    /// Get the total number of releases that happened while
+    /// the previous activation was not completed, yet.
+    pub fn overruns(&self) -> u32 {
+        self.overruns.load(Relaxed)
+    }
+}
+
+struct SignalState<const N: usize> {
+    /// The released tasks of the thread, indexed by task index.
+    pending: [bool; N],
+    /// The task thread shall exit.
+    stop: bool,
+}
+
+/// Wakeup signal of a task thread.
+///
+/// A task thread runs one or more tasks.
+/// Releasing any of these tasks adds it to the pending set of the thread.
+pub struct Signal<const N: usize> {
+    state: Mutex<SignalState<N>>,
+    condvar: Condvar,
+}
+
+impl<const N: usize> Signal<N> {
+    pub const fn new() -> Self {
+        Self {
+            state: Mutex::new(SignalState {
+                pending: [false; N],
+                stop: false,
+            }),
+            condvar: Condvar::new(),
+        }
+    }
+
+    /// Reset the signal to its initial state.
+    pub fn reset(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.pending = [false; N];
+        state.stop = false;
+    }
+
+    /// Add the task `task` to the pending set and wake up the task thread.
+    pub fn notify(&self, task: usize) {
+        self.state.lock().unwrap().pending[task] = true;
+        self.condvar.notify_one();
+    }
+
+    /// Request the task thread to exit after its running activation.
+    pub fn stop(&self) {
+        self.state.lock().unwrap().stop = true;
+        self.condvar.notify_one();
+    }
+
+    /// Check whether the task thread has been requested to exit.
+    pub fn is_stopped(&self) -> bool {
+        self.state.lock().unwrap().stop
+    }
+
+    /// Wait for tasks to be released and take the pending set.
+    ///
+    /// Returns `None`, if the task thread shall exit.
+    pub fn wait(&self) -> Option<[bool; N]> {
+        let mut state = self.state.lock().unwrap();
+        while !state.pending.contains(&true) && !state.stop {
+            state = self.condvar.wait(state).unwrap();
+        }
+        if state.stop {
+            return None;
+        }
+        Some(core::mem::replace(&mut state.pending, [false; N]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PERIOD_US: u64 = 10_000;
+
+    fn pending(trigger: &Trigger) -> u32 {
+        trigger.state.lock().unwrap().pending
+    }
+
+    fn missed(trigger: &Trigger) -> u32 {
+        trigger.state.lock().unwrap().missed
+    }
+
+    #[test]
+    fn test_skip() {
+        let trigger = Trigger::new(PERIOD_US);
+        assert!(trigger.release(OverrunPolicy::Skip, 0));
+        assert_eq!(pending(&trigger), 1);
+
+        // Released while pending.
+        assert!(!trigger.release(OverrunPolicy::Skip, PERIOD_US));
+        assert_eq!(pending(&trigger), 1);
+        assert_eq!(missed(&trigger), 1);
+        assert_eq!(trigger.overruns(), 1);
+
+        let activation = trigger.try_start().unwrap();
+        assert_eq!(activation.number, 0);
+        assert_eq!(activation.release_us, 0);
+        assert_eq!(activation.missed, 1);
+        assert_eq!(pending(&trigger), 0);
+        assert_eq!(missed(&trigger), 0);
+
+        // Released while running.
+        assert!(!trigger.release(OverrunPolicy::Skip, 2 * PERIOD_US));
+        assert_eq!(pending(&trigger), 0);
+        assert_eq!(missed(&trigger), 1);
+        assert_eq!(trigger.overruns(), 2);
+
+        assert!(!trigger.done());
+        assert!(trigger.try_start().is_none());
+
+        assert!(trigger.release(OverrunPolicy::Skip, 3 * PERIOD_US));
+        let activation = trigger.try_start().unwrap();
+        assert_eq!(activation.number, 1);
+        assert_eq!(activation.release_us, 3 * PERIOD_US);
+        assert_eq!(activation.missed, 1);
+        assert!(!trigger.done());
+        assert_eq!(trigger.overruns(), 2);
+    }
+
+    #[test]
+    fn test_queue() {
+        let policy = OverrunPolicy::Queue(2);
+        let trigger = Trigger::new(PERIOD_US);
+        assert!(trigger.release(policy, 0));
+        let activation = trigger.try_start().unwrap();
+        assert_eq!(activation.release_us, 0);
+
+        // Released while running. Two releases are queued, the third is dropped.
+        assert!(trigger.release(policy, PERIOD_US));
+        assert!(trigger.release(policy, 2 * PERIOD_US));
+        assert!(!trigger.release(policy, 3 * PERIOD_US));
+        assert_eq!(pending(&trigger), 2);
+        assert_eq!(missed(&trigger), 1);
+        assert_eq!(trigger.overruns(), 3);
+
+        // The queued activations have consecutive nominal release times.
+        assert!(trigger.done());
+        let activation = trigger.try_start().unwrap();
+        assert_eq!(activation.number, 1);
+        assert_eq!(activation.release_us, PERIOD_US);
+        assert_eq!(activation.missed, 1);
+        assert!(trigger.done());
+        let activation = trigger.try_start().unwrap();
+        assert_eq!(activation.number, 2);
+        assert_eq!(activation.release_us, 2 * PERIOD_US);
+        assert_eq!(activation.missed, 0);
+        assert!(!trigger.done());
+        assert!(trigger.try_start().is_none());
+    }
+
+    #[test]
+    fn test_callback() {
+        let policy = OverrunPolicy::Callback;
+        let trigger = Trigger::new(PERIOD_US);
+        assert!(trigger.release(policy, 0));
+        let _activation = trigger.try_start().unwrap();
+
+        // All releases while running are dropped and counted.
+        for i in 1..=3 {
+            assert!(!trigger.release(policy, i * PERIOD_US));
+        }
+        assert_eq!(pending(&trigger), 0);
+        assert_eq!(missed(&trigger), 3);
+        assert_eq!(trigger.overruns(), 3);
+        assert!(!trigger.done());
+
+        // The next activation reports the dropped releases once.
+        assert!(trigger.release(policy, 4 * PERIOD_US));
+        let activation = trigger.try_start().unwrap();
+        assert_eq!(activation.missed, 3);
+        assert!(!trigger.done());
+        assert!(trigger.release(policy, 5 * PERIOD_US));
+        let activation = trigger.try_start().unwrap();
+        assert_eq!(activation.missed, 0);
+        assert!(!trigger.done());
+    }
+
+    #[test]
+    fn test_drop_pending_and_reset() {
+        let policy = OverrunPolicy::Queue(3);
+        let trigger = Trigger::new(PERIOD_US);
+        assert!(trigger.release(policy, 0));
+        assert!(trigger.release(policy, PERIOD_US));
+        trigger.drop_pending();
+        assert_eq!(pending(&trigger), 0);
+        assert!(trigger.try_start().is_none());
+        assert_eq!(trigger.overruns(), 1);
+
+        trigger.reset();
+        assert_eq!(trigger.overruns(), 0);
+        assert!(trigger.release(policy, 0));
+        assert_eq!(trigger.try_start().unwrap().number, 0);
+        assert!(!trigger.done());
+    }
+
+    #[test]
+    fn test_signal() {
+        let signal: Signal<3> = Signal::new();
+        signal.notify(2);
+        signal.notify(0);
+        assert_eq!(signal.wait(), Some([true, false, true]));
+        signal.notify(1);
+        assert!(!signal.is_stopped());
+        signal.stop();
+        assert!(signal.is_stopped());
+        assert_eq!(signal.wait(), None);
+        signal.reset();
+        assert!(!signal.is_stopped());
+        signal.notify(1);
+        assert_eq!(signal.wait(), Some([false, true, false]));
+    }
+}
+
+// vim: ts=4 sw=4 expandtab